@@ -0,0 +1,19 @@
+// a circle with a whole number radius, described by its `Display`.
+// generics shares this file and makes it one of its shapes.
+
+#![allow(dead_code)]
+
+use std::fmt;
+use std::fmt::Formatter;
+
+pub struct Circle {
+    pub radius: i32,
+}
+
+// implement the fmt::Display trait for type which will automagically provides ToString
+// and also allows printing the type on print!
+impl fmt::Display for Circle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Circle of radius {}", self.radius)
+    }
+}
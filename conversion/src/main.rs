@@ -1,5 +1,8 @@
-use std::fmt;
-use std::fmt::{Error, Formatter};
+use std::fmt::Error;
+
+mod circle;
+
+use circle::Circle;
 
 #[derive(Debug)]
 struct Number {
@@ -12,18 +15,6 @@ impl From<i32> for Number {
     }
 }
 
-struct Circle {
-    radius: i32,
-}
-
-// implement the fmt::Display trait for type which will automagically provides ToString
-// and also allows printing the type on print!
-impl fmt::Display for Circle {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "Circle of radius {}", self.radius)
-    }
-}
-
 fn main() {
     let my_str = "hello";
     let my_string = String::from(my_str);
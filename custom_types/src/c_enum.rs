@@ -0,0 +1,199 @@
+// checked conversions for C-like enums.
+// `c_enum!` wraps the declaration of an enum whose variants carry no data and implements
+// `CEnum` for it: the list of variants, their names and discriminants, plus `TryFrom<i32>`,
+// `TryFrom<u32>`, `FromStr` and `Display` going through that list, so an integer or a name
+// only turns into a variant that really exists.
+
+use std::error::Error;
+use std::fmt;
+
+pub trait CEnum: Sized + Copy + 'static {
+    // the name of the enum itself, used in errors
+    const NAME: &'static str;
+    // every variant in declaration order
+    const VARIANTS: &'static [Self];
+    const COUNT: usize = Self::VARIANTS.len();
+
+    fn name(self) -> &'static str;
+    fn discriminant(self) -> i64;
+
+    fn iter() -> std::iter::Copied<std::slice::Iter<'static, Self>> {
+        Self::VARIANTS.iter().copied()
+    }
+
+    fn from_discriminant(value: i64) -> Result<Self, ConversionError> {
+        Self::iter()
+            .find(|v| v.discriminant() == value)
+            .ok_or(ConversionError::Value {
+                enum_name: Self::NAME,
+                value,
+            })
+    }
+
+    fn from_name(name: &str) -> Result<Self, ConversionError> {
+        Self::iter()
+            .find(|v| v.name() == name)
+            .ok_or_else(|| ConversionError::Name {
+                enum_name: Self::NAME,
+                name: name.to_owned(),
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    Value {
+        enum_name: &'static str,
+        value: i64,
+    },
+    Name {
+        enum_name: &'static str,
+        name: String,
+    },
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // hex of a negative i64 would be its two's complement, not what was passed
+            ConversionError::Value { enum_name, value } if *value < 0 => {
+                write!(f, "no {} has the value {}", enum_name, value)
+            }
+            ConversionError::Value { enum_name, value } => {
+                write!(f, "no {} has the value {:#x}", enum_name, value)
+            }
+            ConversionError::Name { enum_name, name } => {
+                write!(f, "no {} is called {:?}", enum_name, name)
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+macro_rules! c_enum {
+    (
+        $(#[$meta:meta])*
+        enum $name:ident {
+            $($variant:ident $(= $value:expr)?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum $name {
+            $($variant $(= $value)?),*
+        }
+
+        impl $crate::c_enum::CEnum for $name {
+            const NAME: &'static str = stringify!($name);
+            const VARIANTS: &'static [Self] = &[$($name::$variant),*];
+
+            fn name(self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant)),*
+                }
+            }
+
+            fn discriminant(self) -> i64 {
+                self as i64
+            }
+        }
+
+        impl ::std::convert::TryFrom<i32> for $name {
+            type Error = $crate::c_enum::ConversionError;
+
+            fn try_from(value: i32) -> Result<Self, Self::Error> {
+                <$name as $crate::c_enum::CEnum>::from_discriminant(value.into())
+            }
+        }
+
+        impl ::std::convert::TryFrom<u32> for $name {
+            type Error = $crate::c_enum::ConversionError;
+
+            fn try_from(value: u32) -> Result<Self, Self::Error> {
+                <$name as $crate::c_enum::CEnum>::from_discriminant(value.into())
+            }
+        }
+
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::c_enum::ConversionError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                <$name as $crate::c_enum::CEnum>::from_name(s)
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str($crate::c_enum::CEnum::name(*self))
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Number, Status, Work};
+    use std::convert::TryFrom;
+
+    #[test]
+    fn test_integer_round_trip() {
+        for color in Color::iter() {
+            assert_eq!(Color::try_from(color as i32), Ok(color));
+            assert_eq!(Color::try_from(color as u32), Ok(color));
+        }
+        assert_eq!(Number::try_from(2), Ok(Number::Two));
+        assert_eq!(
+            Number::try_from(3),
+            Err(ConversionError::Value {
+                enum_name: "Number",
+                value: 3
+            })
+        );
+        assert_eq!(
+            Color::try_from(0x123456u32).unwrap_err().to_string(),
+            "no Color has the value 0x123456"
+        );
+        assert_eq!(
+            Color::try_from(-1i32).unwrap_err().to_string(),
+            "no Color has the value -1"
+        );
+        // larger than any i32, still reported as it was given
+        assert_eq!(
+            Work::try_from(u32::MAX),
+            Err(ConversionError::Value {
+                enum_name: "Work",
+                value: u32::MAX.into()
+            })
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        for color in Color::iter() {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+        assert_eq!("Red".parse(), Ok(Color::Red));
+        assert_eq!(Color::Blue.name(), "Blue");
+        assert_eq!(
+            "red".parse::<Color>().unwrap_err().to_string(),
+            "no Color is called \"red\""
+        );
+        assert_eq!(Status::from_name("Poor"), Ok(Status::Poor));
+    }
+
+    #[test]
+    fn test_variants() {
+        assert_eq!(Number::COUNT, 3);
+        assert_eq!(Work::COUNT, 2);
+        assert_eq!(
+            Color::iter().map(|c| c as i32).collect::<Vec<_>>(),
+            vec![0xff0000, 0x00ff00, 0x0000ff]
+        );
+        assert_eq!(
+            Status::iter().map(Status::name).collect::<Vec<_>>(),
+            vec!["Rich", "Poor"]
+        );
+    }
+}
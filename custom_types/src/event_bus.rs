@@ -0,0 +1,373 @@
+// an event bus for `WebEvent`.
+// handlers subscribe to one kind of event and run in the order they subscribed, each one
+// decides whether the event goes on to the next handler.
+// every dispatched event is recorded in an `EventLog`, which writes one event per line and
+// parses that text back, so a recorded session can be replayed into another bus.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::WebEvent;
+
+// the variants of `WebEvent` without their data, what handlers subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    PageLoad,
+    PageUnLoad,
+    KeyPress,
+    Paste,
+    Click,
+}
+
+impl WebEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            WebEvent::PageLoad => EventKind::PageLoad,
+            WebEvent::PageUnLoad => EventKind::PageUnLoad,
+            WebEvent::KeyPress(_) => EventKind::KeyPress,
+            WebEvent::Paste(_) => EventKind::Paste,
+            WebEvent::Click { .. } => EventKind::Click,
+        }
+    }
+}
+
+// returned by a handler to let the event through or to swallow it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    Continue,
+    Stop,
+}
+
+type Handler = Box<dyn FnMut(&WebEvent) -> Propagation>;
+
+#[derive(Default)]
+pub struct EventBus {
+    // kept in one list so registration order is the calling order
+    handlers: Vec<(EventKind, Handler)>,
+    log: EventLog,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus::default()
+    }
+
+    pub fn subscribe<F>(&mut self, kind: EventKind, handler: F)
+    where
+        F: FnMut(&WebEvent) -> Propagation + 'static,
+    {
+        self.handlers.push((kind, Box::new(handler)));
+    }
+
+    // record `event` and run its handlers, return how many of them ran
+    pub fn dispatch(&mut self, event: WebEvent) -> usize {
+        let kind = event.kind();
+        let mut called = 0;
+        for (_, handler) in self.handlers.iter_mut().filter(|(k, _)| *k == kind) {
+            called += 1;
+            if handler(&event) == Propagation::Stop {
+                break;
+            }
+        }
+        self.log.events.push(event);
+        called
+    }
+
+    pub fn log(&self) -> &EventLog {
+        &self.log
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EventLog {
+    events: Vec<WebEvent>,
+}
+
+impl EventLog {
+    pub fn events(&self) -> &[WebEvent] {
+        &self.events
+    }
+
+    // dispatch every recorded event into `bus`, in the order they happened
+    pub fn replay(&self, bus: &mut EventBus) {
+        for event in self.events.iter() {
+            bus.dispatch(event.clone());
+        }
+    }
+}
+
+// one event per line
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in self.events.iter() {
+            writeln!(f, "{}", event)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EventLog {
+    type Err = ParseEventError;
+
+    // blank lines are skipped, errors carry the 1-based line number
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut events = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = line
+                .parse()
+                .map_err(|err: ParseEventError| ParseEventError {
+                    line: number + 1,
+                    ..err
+                })?;
+            events.push(event);
+        }
+        Ok(EventLog { events })
+    }
+}
+
+// escape `\`, the quote in use and line breaks so every event stays on one line
+fn escape(s: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// the text between `quote`s with escapes undone, `None` when it is not well formed
+fn unescape(s: &str, quote: char) -> Option<String> {
+    let body = s.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut unescaped = String::new();
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                c @ ('\\' | '"' | '\'') => c,
+                _ => return None,
+            },
+            c if c == quote => return None,
+            c => c,
+        });
+    }
+    Some(unescaped)
+}
+
+// PageLoad, PageUnLoad, KeyPress 'x', Paste "my_test", Click 20 80
+impl fmt::Display for WebEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebEvent::PageLoad => write!(f, "PageLoad"),
+            WebEvent::PageUnLoad => write!(f, "PageUnLoad"),
+            WebEvent::KeyPress(c) => write!(f, "KeyPress '{}'", escape(&c.to_string(), '\'')),
+            WebEvent::Paste(s) => write!(f, "Paste \"{}\"", escape(s, '"')),
+            WebEvent::Click { x, y } => write!(f, "Click {} {}", x, y),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseEventError {
+    // 0 when a single event was parsed, the line number inside an `EventLog`
+    pub line: usize,
+    pub text: String,
+}
+
+impl fmt::Display for ParseEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "invalid event: {:?}", self.text)
+        } else {
+            write!(f, "invalid event on line {}: {:?}", self.line, self.text)
+        }
+    }
+}
+
+impl Error for ParseEventError {}
+
+impl FromStr for WebEvent {
+    type Err = ParseEventError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseEventError {
+            line: 0,
+            text: s.to_owned(),
+        };
+        let (name, rest) = match s.split_once(' ') {
+            Some((name, rest)) => (name, rest),
+            None => (s, ""),
+        };
+
+        match (name, rest) {
+            ("PageLoad", "") => Ok(WebEvent::PageLoad),
+            ("PageUnLoad", "") => Ok(WebEvent::PageUnLoad),
+            ("KeyPress", rest) => {
+                let key = unescape(rest, '\'').ok_or_else(invalid)?;
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(WebEvent::KeyPress(c)),
+                    _ => Err(invalid()),
+                }
+            }
+            ("Paste", rest) => Ok(WebEvent::Paste(unescape(rest, '"').ok_or_else(invalid)?)),
+            ("Click", rest) => {
+                let (x, y) = rest.split_once(' ').ok_or_else(invalid)?;
+                Ok(WebEvent::Click {
+                    x: x.parse().map_err(|_| invalid())?,
+                    y: y.parse().map_err(|_| invalid())?,
+                })
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    // a handler that appends `name: event` to `calls`
+    fn recorder(
+        calls: &Rc<RefCell<Vec<String>>>,
+        name: &'static str,
+        propagation: Propagation,
+    ) -> impl FnMut(&WebEvent) -> Propagation {
+        let calls = Rc::clone(calls);
+        move |event| {
+            calls.borrow_mut().push(format!("{}: {}", name, event));
+            propagation
+        }
+    }
+
+    fn session() -> Vec<WebEvent> {
+        vec![
+            WebEvent::PageLoad,
+            WebEvent::KeyPress('x'),
+            WebEvent::KeyPress('\''),
+            WebEvent::Paste("line one\nsaid \"hi\" \\ bye\t!".to_owned()),
+            WebEvent::Click { x: 20, y: -80 },
+            WebEvent::PageUnLoad,
+        ]
+    }
+
+    #[test]
+    fn test_handlers_run_in_registration_order_per_kind() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        bus.subscribe(
+            EventKind::Click,
+            recorder(&calls, "first", Propagation::Continue),
+        );
+        bus.subscribe(
+            EventKind::KeyPress,
+            recorder(&calls, "key", Propagation::Continue),
+        );
+        bus.subscribe(
+            EventKind::Click,
+            recorder(&calls, "second", Propagation::Continue),
+        );
+
+        assert_eq!(bus.dispatch(WebEvent::Click { x: 1, y: 2 }), 2);
+        assert_eq!(bus.dispatch(WebEvent::PageLoad), 0);
+        assert_eq!(
+            *calls.borrow(),
+            vec!["first: Click 1 2", "second: Click 1 2"]
+        );
+    }
+
+    #[test]
+    fn test_stop_propagation() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        bus.subscribe(
+            EventKind::Paste,
+            recorder(&calls, "guard", Propagation::Stop),
+        );
+        bus.subscribe(
+            EventKind::Paste,
+            recorder(&calls, "never", Propagation::Continue),
+        );
+
+        assert_eq!(bus.dispatch(WebEvent::Paste("secret".to_owned())), 1);
+        assert_eq!(*calls.borrow(), vec!["guard: Paste \"secret\""]);
+        // stopped events are still recorded
+        assert_eq!(bus.log().events().len(), 1);
+    }
+
+    #[test]
+    fn test_event_text_round_trip() {
+        for event in session() {
+            assert_eq!(event.to_string().parse::<WebEvent>(), Ok(event));
+        }
+        assert_eq!(WebEvent::KeyPress('\n').to_string(), "KeyPress '\\n'");
+        for bad in [
+            "Scroll 1",
+            "PageLoad now",
+            "KeyPress 'ab'",
+            "Paste \"x",
+            "Click 1",
+        ]
+        .iter()
+        {
+            assert!(bad.parse::<WebEvent>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_log_round_trip_and_replay() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = EventBus::new();
+        for &kind in [EventKind::PageLoad, EventKind::KeyPress, EventKind::Paste].iter() {
+            bus.subscribe(kind, recorder(&calls, "live", Propagation::Continue));
+        }
+        for event in session() {
+            bus.dispatch(event);
+        }
+
+        let text = bus.log().to_string();
+        assert_eq!(text.lines().count(), session().len());
+        let log: EventLog = text.parse().unwrap();
+        assert_eq!(&log, bus.log());
+
+        // replaying into a bus with the same handlers reproduces the same calls
+        let replayed = Rc::new(RefCell::new(Vec::new()));
+        let mut replay_bus = EventBus::new();
+        for &kind in [EventKind::PageLoad, EventKind::KeyPress, EventKind::Paste].iter() {
+            replay_bus.subscribe(kind, recorder(&replayed, "live", Propagation::Continue));
+        }
+        log.replay(&mut replay_bus);
+        assert_eq!(*replayed.borrow(), *calls.borrow());
+        assert_eq!(replay_bus.log(), &log);
+    }
+
+    #[test]
+    fn test_log_errors_name_the_line() {
+        let err = "PageLoad\n\nClick 1 x\n".parse::<EventLog>().unwrap_err();
+        assert_eq!(
+            err,
+            ParseEventError {
+                line: 3,
+                text: "Click 1 x".to_owned()
+            }
+        );
+        assert_eq!(err.to_string(), "invalid event on line 3: \"Click 1 x\"");
+    }
+}
@@ -0,0 +1,357 @@
+// geometry on `Rectangle`: a normalised rectangle keeps its minimum corner in `p1` and its
+// maximum corner in `p2`, every operation here normalises its inputs first so the corner
+// order a rectangle was built with never matters.
+// `Canvas` draws rectangles as ASCII outlines, which is what `Rectangle`'s `Display` uses.
+
+use std::fmt;
+
+use super::{Point, Rectangle};
+
+impl Rectangle {
+    // build a normalised rectangle from any two opposite corners
+    pub fn new(a: Point, b: Point) -> Rectangle {
+        Rectangle {
+            p1: Point {
+                x: a.x.min(b.x),
+                y: a.y.min(b.y),
+            },
+            p2: Point {
+                x: a.x.max(b.x),
+                y: a.y.max(b.y),
+            },
+        }
+    }
+
+    pub fn normalize(&self) -> Rectangle {
+        Rectangle::new(self.p1, self.p2)
+    }
+
+    pub fn width(&self) -> f32 {
+        (self.p2.x - self.p1.x).abs()
+    }
+
+    pub fn height(&self) -> f32 {
+        (self.p2.y - self.p1.y).abs()
+    }
+
+    pub fn area(&self) -> f32 {
+        self.width() * self.height()
+    }
+
+    // edges count as inside
+    pub fn contains(&self, point: &Point) -> bool {
+        let r = self.normalize();
+        r.p1.x <= point.x && point.x <= r.p2.x && r.p1.y <= point.y && point.y <= r.p2.y
+    }
+
+    // true when the interiors overlap, rectangles that only touch do not
+    pub fn overlaps(&self, other: &Rectangle) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // the overlapping area, `None` unless it is larger than zero
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let (a, b) = (self.normalize(), other.normalize());
+        let p1 = Point {
+            x: a.p1.x.max(b.p1.x),
+            y: a.p1.y.max(b.p1.y),
+        };
+        let p2 = Point {
+            x: a.p2.x.min(b.p2.x),
+            y: a.p2.y.min(b.p2.y),
+        };
+        if p1.x < p2.x && p1.y < p2.y {
+            Some(Rectangle { p1, p2 })
+        } else {
+            None
+        }
+    }
+
+    // the smallest rectangle containing both
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        let (a, b) = (self.normalize(), other.normalize());
+        Rectangle {
+            p1: Point {
+                x: a.p1.x.min(b.p1.x),
+                y: a.p1.y.min(b.p1.y),
+            },
+            p2: Point {
+                x: a.p2.x.max(b.p2.x),
+                y: a.p2.y.max(b.p2.y),
+            },
+        }
+    }
+
+    // the bounding box of all `rects`, `None` for an empty slice
+    pub fn bounding_box(rects: &[Rectangle]) -> Option<Rectangle> {
+        let (first, rest) = rects.split_first()?;
+        Some(rest.iter().fold(first.normalize(), |acc, r| acc.union(r)))
+    }
+
+    // move every edge inwards by `d`, `None` when the rectangle would turn inside out
+    pub fn inset(&self, d: f32) -> Option<Rectangle> {
+        let r = self.normalize();
+        if 2.0 * d > r.width() || 2.0 * d > r.height() {
+            return None;
+        }
+        Some(Rectangle {
+            p1: Point {
+                x: r.p1.x + d,
+                y: r.p1.y + d,
+            },
+            p2: Point {
+                x: r.p2.x - d,
+                y: r.p2.y - d,
+            },
+        })
+    }
+
+    // move every edge outwards by `d`, a negative `d` that collapses the rectangle gives `None`
+    pub fn outset(&self, d: f32) -> Option<Rectangle> {
+        self.inset(-d)
+    }
+
+    // split into `rows` x `cols` equal cells, row by row starting at the minimum corner
+    pub fn split(&self, rows: usize, cols: usize) -> Vec<Rectangle> {
+        let r = self.normalize();
+        let cell_width = r.width() / cols as f32;
+        let cell_height = r.height() / rows as f32;
+
+        let mut cells = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let x = r.p1.x + cell_width * col as f32;
+                let y = r.p1.y + cell_height * row as f32;
+                // the last row and column end exactly on the outer edge
+                let x2 = if col + 1 == cols {
+                    r.p2.x
+                } else {
+                    x + cell_width
+                };
+                let y2 = if row + 1 == rows {
+                    r.p2.y
+                } else {
+                    y + cell_height
+                };
+                cells.push(Rectangle {
+                    p1: Point { x, y },
+                    p2: Point { x: x2, y: y2 },
+                });
+            }
+        }
+        cells
+    }
+}
+
+// a grid of chars showing `bounds`, y grows upwards like on paper
+pub struct Canvas {
+    bounds: Rectangle,
+    cells: Vec<Vec<char>>,
+}
+
+impl Canvas {
+    pub fn new(bounds: &Rectangle, cols: usize, rows: usize) -> Canvas {
+        Canvas {
+            bounds: bounds.normalize(),
+            cells: vec![vec![' '; cols.max(1)]; rows.max(1)],
+        }
+    }
+
+    // one char per unit, at most 60 x 20 chars, larger bounds are scaled down.
+    // clamped before the cast, a width over `usize::MAX` (or infinite) would overflow the `+ 1`
+    pub fn fit(bounds: &Rectangle) -> Canvas {
+        let cols = (bounds.width().ceil().min(59.0) as usize + 1).max(2);
+        let rows = (bounds.height().ceil().min(19.0) as usize + 1).max(2);
+        Canvas::new(bounds, cols, rows)
+    }
+
+    // a canvas showing all of `rects`
+    pub fn with_rects(rects: &[Rectangle]) -> Canvas {
+        let bounds = Rectangle::bounding_box(rects).unwrap_or(Rectangle {
+            p1: Point { x: 0.0, y: 0.0 },
+            p2: Point { x: 0.0, y: 0.0 },
+        });
+        let mut canvas = Canvas::fit(&bounds);
+        for rect in rects {
+            canvas.draw(rect);
+        }
+        canvas
+    }
+
+    // positions are scaled in f64, where the width of even `f32::MIN..f32::MAX` is finite
+    fn column(&self, x: f32) -> usize {
+        let cols = self.cells[0].len() - 1;
+        let width = f64::from(self.bounds.p2.x) - f64::from(self.bounds.p1.x);
+        if width == 0.0 {
+            return 0;
+        }
+        (((f64::from(x) - f64::from(self.bounds.p1.x)) / width * cols as f64)
+            .round()
+            .max(0.0) as usize)
+            .min(cols)
+    }
+
+    fn row(&self, y: f32) -> usize {
+        let rows = self.cells.len() - 1;
+        let height = f64::from(self.bounds.p2.y) - f64::from(self.bounds.p1.y);
+        if height == 0.0 {
+            return 0;
+        }
+        let from_bottom = ((f64::from(y) - f64::from(self.bounds.p1.y)) / height * rows as f64)
+            .round()
+            .max(0.0) as usize;
+        rows - from_bottom.min(rows)
+    }
+
+    // draw the outline of `rect` with `+` corners, `-` and `|` edges
+    pub fn draw(&mut self, rect: &Rectangle) {
+        let r = rect.normalize();
+        let (left, right) = (self.column(r.p1.x), self.column(r.p2.x));
+        let (top, bottom) = (self.row(r.p2.y), self.row(r.p1.y));
+
+        for col in left..=right {
+            self.cells[top][col] = '-';
+            self.cells[bottom][col] = '-';
+        }
+        for row in top..=bottom {
+            self.cells[row][left] = '|';
+            self.cells[row][right] = '|';
+        }
+        for &(row, col) in [(top, left), (top, right), (bottom, left), (bottom, right)].iter() {
+            self.cells[row][col] = '+';
+        }
+    }
+}
+
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.iter() {
+            let line: String = row.iter().collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Canvas::with_rects(&[*self]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x1: f32, y1: f32, x2: f32, y2: f32) -> Rectangle {
+        Rectangle {
+            p1: Point { x: x1, y: y1 },
+            p2: Point { x: x2, y: y2 },
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            rect(3.0, 4.0, 1.0, 0.0).normalize(),
+            rect(1.0, 0.0, 3.0, 4.0)
+        );
+        assert_eq!(
+            Rectangle::new(Point { x: 1.0, y: 4.0 }, Point { x: 3.0, y: 0.0 }),
+            rect(1.0, 0.0, 3.0, 4.0)
+        );
+        assert_eq!(rect(3.0, 4.0, 1.0, 0.0).area(), 8.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let r = rect(2.0, 2.0, 0.0, 0.0);
+        assert!(r.contains(&Point { x: 1.0, y: 1.0 }));
+        assert!(r.contains(&Point { x: 2.0, y: 0.0 }));
+        assert!(!r.contains(&Point { x: 2.1, y: 1.0 }));
+    }
+
+    #[test]
+    fn test_intersection_union_overlap() {
+        let a = rect(0.0, 0.0, 4.0, 4.0);
+        let b = rect(6.0, 6.0, 2.0, 2.0);
+        assert_eq!(a.intersection(&b), Some(rect(2.0, 2.0, 4.0, 4.0)));
+        assert!(a.overlaps(&b));
+        assert_eq!(a.union(&b), rect(0.0, 0.0, 6.0, 6.0));
+
+        // touching edges do not overlap
+        let c = rect(4.0, 0.0, 5.0, 4.0);
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.intersection(&c), None);
+
+        assert_eq!(
+            Rectangle::bounding_box(&[a, c, rect(-1.0, 1.0, 0.0, 2.0)]),
+            Some(rect(-1.0, 0.0, 5.0, 4.0))
+        );
+        assert_eq!(Rectangle::bounding_box(&[]), None);
+    }
+
+    #[test]
+    fn test_inset_outset() {
+        let r = rect(0.0, 0.0, 4.0, 2.0);
+        assert_eq!(r.inset(0.5), Some(rect(0.5, 0.5, 3.5, 1.5)));
+        assert_eq!(r.inset(1.0), Some(rect(1.0, 1.0, 3.0, 1.0)));
+        assert_eq!(r.inset(1.5), None);
+        assert_eq!(r.outset(1.0), Some(rect(-1.0, -1.0, 5.0, 3.0)));
+    }
+
+    #[test]
+    fn test_split() {
+        let cells = rect(0.0, 0.0, 3.0, 2.0).split(2, 3);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], rect(0.0, 0.0, 1.0, 1.0));
+        assert_eq!(cells[5], rect(2.0, 1.0, 3.0, 2.0));
+        let total: f32 = cells.iter().map(Rectangle::area).sum();
+        assert_eq!(total, 6.0);
+    }
+
+    #[test]
+    fn test_display_draws_outline() {
+        assert_eq!(
+            rect(0.0, 0.0, 3.0, 2.0).to_string(),
+            "\
++--+
+|  |
++--+
+"
+        );
+    }
+
+    #[test]
+    fn test_canvas_with_several_rects() {
+        let outer = rect(0.0, 0.0, 8.0, 6.0);
+        let inner = outer.inset(2.0).unwrap();
+        assert_eq!(
+            Canvas::with_rects(&[outer, inner]).to_string(),
+            "\
++-------+
+|       |
+| +---+ |
+| |   | |
+| +---+ |
+|       |
++-------+
+"
+        );
+    }
+
+    #[test]
+    fn test_canvas_with_huge_bounds() {
+        let everything = rect(f32::MIN, f32::MIN, f32::MAX, f32::MAX);
+        let drawn = Canvas::with_rects(&[everything]).to_string();
+        let lines: Vec<&str> = drawn.lines().collect();
+        assert_eq!(lines.len(), 20);
+        assert_eq!(lines[0], format!("+{}+", "-".repeat(58)));
+        assert_eq!(lines[1], format!("|{}|", " ".repeat(58)));
+
+        let wide = rect(0.0, 0.0, f32::INFINITY, 1.0);
+        let canvas = Canvas::fit(&wide);
+        assert_eq!(canvas.to_string().lines().count(), 2);
+        Canvas::with_rects(&[wide, rect(0.0, 0.0, 1e30, 1.0)]);
+    }
+}
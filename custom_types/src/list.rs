@@ -0,0 +1,276 @@
+// a persistent singly linked list.
+// nodes are shared through `Rc`, so `prepend` borrows the list and returns a new one whose
+// tail is the old list: both stay usable and no element is copied.
+// everything that walks the list is a loop, never recursion, so a list of millions of
+// elements neither overflows the stack when measured, printed nor dropped.
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::rc::Rc;
+
+struct Node<T> {
+    elem: T,
+    next: Link<T>,
+}
+
+type Link<T> = Option<Rc<Node<T>>>;
+
+pub struct List<T> {
+    head: Link<T>,
+}
+
+impl<T> List<T> {
+    pub fn new() -> List<T> {
+        List { head: None }
+    }
+
+    // return a new list with `elem` at its front, sharing `self` as its tail
+    pub fn prepend(&self, elem: T) -> List<T> {
+        List {
+            head: Some(Rc::new(Node {
+                elem,
+                next: self.head.clone(),
+            })),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.elem)
+    }
+
+    // the list without its first element, empty stays empty
+    pub fn tail(&self) -> List<T> {
+        List {
+            head: self.head.as_ref().and_then(|node| node.next.clone()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    // return the length of the list
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    pub fn map<U, F>(&self, f: F) -> List<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        self.iter().map(f).collect()
+    }
+}
+
+impl<T: Clone> List<T> {
+    pub fn reverse(&self) -> List<T> {
+        let mut reversed = List::new();
+        for elem in self.iter() {
+            reversed = reversed.prepend(elem.clone());
+        }
+        reversed
+    }
+
+    // `self` followed by `other`, the nodes of `other` are shared rather than copied
+    pub fn append(&self, other: &List<T>) -> List<T> {
+        let mut list = other.clone();
+        for elem in self.reverse().iter() {
+            list = list.prepend(elem.clone());
+        }
+        list
+    }
+}
+
+impl<T: fmt::Display> List<T> {
+    // return representation of the list as a (heap allocated) string
+    pub fn stringify(&self) -> String {
+        let mut s = String::new();
+        for elem in self.iter() {
+            s.push_str(&format!("{}, ", elem));
+        }
+        s.push_str("Nil");
+        s
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+// cloning a list only bumps the reference count of its head
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
+        }
+    }
+}
+
+// the derived `Drop` would recurse once per node, so unlink the nodes one by one instead.
+// the walk stops at the first node another list still holds on to.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(node) = link {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => link = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.elem
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// by value iteration moves elements out of the nodes this list owns alone,
+// and clones those still shared with another list
+pub struct IntoIter<T> {
+    list: List<T>,
+}
+
+impl<T: Clone> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.head.take()?;
+        match Rc::try_unwrap(node) {
+            Ok(mut node) => {
+                self.list.head = node.next.take();
+                Some(node.elem)
+            }
+            Err(node) => {
+                self.list.head = node.next.clone();
+                Some(node.elem.clone())
+            }
+        }
+    }
+}
+
+impl<T: Clone> IntoIterator for List<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+// the first element of the iterator becomes the head of the list
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elems: Vec<T> = iter.into_iter().collect();
+        let mut list = List::new();
+        for elem in elems.into_iter().rev() {
+            list = list.prepend(elem);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prepend_shares_the_original() {
+        let list = List::new().prepend(1).prepend(2);
+        let longer = list.prepend(3);
+        assert_eq!(list.len(), 2);
+        assert_eq!(longer.len(), 3);
+        assert_eq!(longer.tail(), list);
+        assert_eq!(list.head(), Some(&2));
+        assert_eq!(longer.stringify(), "3, 2, 1, Nil");
+        assert_eq!(List::<u32>::new().stringify(), "Nil");
+    }
+
+    #[test]
+    fn test_iterators() {
+        let list: List<i32> = (1..=4).collect();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!((&list).into_iter().sum::<i32>(), 10);
+
+        let shared = list.tail();
+        assert_eq!(list.into_iter().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        // the nodes `shared` points to are cloned out, not taken
+        assert_eq!(shared.into_iter().collect::<Vec<_>>(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reverse_append_map() {
+        let a: List<i32> = vec![1, 2, 3].into_iter().collect();
+        let b: List<i32> = vec![4, 5].into_iter().collect();
+        assert_eq!(a.reverse(), vec![3, 2, 1].into_iter().collect());
+        assert_eq!(a.append(&b), (1..=5).collect());
+        assert_eq!(a.append(&List::new()), a);
+        assert_eq!(a.map(|x| x.to_string()).stringify(), "1, 2, 3, Nil");
+        assert_eq!(format!("{:?}", b), "[4, 5]");
+    }
+
+    #[test]
+    fn test_drop_keeps_shared_tail() {
+        let tail: List<String> = vec!["b".to_owned(), "c".to_owned()].into_iter().collect();
+        let list = tail.prepend("a".to_owned());
+        drop(tail);
+        assert_eq!(list.stringify(), "a, b, c, Nil");
+    }
+
+    #[test]
+    fn test_million_elements_do_not_overflow_the_stack() {
+        let mut list = List::new();
+        for i in 0..1_000_000u32 {
+            list = list.prepend(i);
+        }
+        let shared = list.tail();
+        assert_eq!(list.len(), 1_000_000);
+        assert_eq!(list.reverse().head(), Some(&0));
+        assert!(list.stringify().ends_with("1, 0, Nil"));
+        drop(list);
+        assert_eq!(shared.len(), 999_999);
+        drop(shared);
+
+        let collected: List<u64> = (0..1_000_000).collect();
+        assert_eq!(collected.into_iter().count(), 1_000_000);
+    }
+}
@@ -6,7 +6,15 @@
 // an attribute to hide warnings for unused code.
 #![allow(dead_code)]
 
-use crate::List::Cons;
+#[macro_use]
+mod c_enum;
+mod event_bus;
+mod geometry;
+mod list;
+
+use c_enum::CEnum;
+use list::List;
+use std::convert::TryFrom;
 
 #[derive(Debug)]
 struct Person<'a> {
@@ -21,7 +29,7 @@ struct Nil;
 struct Pair(i32, f32);
 
 // a struct with two fields
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 struct Point {
     x: f32,
     y: f32,
@@ -29,7 +37,7 @@ struct Point {
 
 // struct can be reused as fields of another struct
 //#[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 struct Rectangle {
     p1: Point,
     p2: Point,
@@ -56,6 +64,7 @@ fn square(point: &Point, width: f32) -> Rectangle {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 enum WebEvent {
     // an `enum` may either by `unit-like`
     PageLoad,
@@ -79,64 +88,35 @@ fn inspect(event: WebEvent) {
     }
 }
 
-enum Status {
-    Rich,
-    Poor,
+c_enum! {
+    enum Status {
+        Rich,
+        Poor,
+    }
 }
 
-enum Work {
-    Civilian,
-    Soldier,
+c_enum! {
+    enum Work {
+        Civilian,
+        Soldier,
+    }
 }
 
 // enum with implicit discriminator(starts at 0)
-enum Number {
-    Zero,
-    One,
-    Two,
+c_enum! {
+    enum Number {
+        Zero,
+        One,
+        Two,
+    }
 }
 
 // enum with explicit discriminator
-enum Color {
-    Red = 0xff0000,
-    Green = 0x00ff00,
-    Blue = 0x0000ff,
-}
-
-enum List {
-    // Cons: Tuple struct that wraps an element and a pointer to the next node
-    Cons(u32, Box<List>),
-    // Nil: a node that signifies the end of the linked list
-    Nil,
-}
-
-impl List {
-    fn new() -> List {
-        List::Nil
-    }
-
-    // consume a list, and return the same list with a new element at its front
-    fn prepend(self, elem: u32) -> List {
-        Cons(elem, Box::new(self))
-    }
-
-    // return the length of the list
-    fn len(&self) -> u32 {
-        match *self {
-            // `self` has to be matched, because the behavior of this method depends on the variant of `self`
-            // `self` has type `&List`, and `*self` has type `List`, matching on a concrete type `T`
-            // is preferred over a match on a reference `&T`
-            List::Nil => 0,
-            List::Cons(_, ref tail) => 1 + tail.len(),
-        }
-    }
-
-    // return representation of the list as a (heap allocated) string
-    fn stringify(&self) -> String {
-        match *self {
-            List::Nil => format!("Nil"),
-            List::Cons(head, ref tail) => format!("{}, {}", head, tail.stringify()),
-        }
+c_enum! {
+    enum Color {
+        Red = 0xff0000,
+        Green = 0x00ff00,
+        Blue = 0x0000ff,
     }
 }
 
@@ -178,6 +158,16 @@ fn main() {
         rect_area(&square(&_rectangle.p1, 3.0))
     );
 
+    // corners in any order, normalised by the geometry operations
+    let other = Rectangle::new(Point { x: 2.0, y: 1.0 }, Point { x: 0.5, y: 0.0 });
+    println!("normalised: {:?}", other);
+    println!("overlaps: {}", _rectangle.overlaps(&other));
+    println!("intersection: {:?}", _rectangle.intersection(&other));
+    println!("union: {:?}", _rectangle.union(&other));
+    let board = square(&Point { x: 0.0, y: 0.0 }, 8.0);
+    println!("{}", board);
+    print!("{}", geometry::Canvas::with_rects(&board.split(2, 2)));
+
     // instantiate a unit struct
     let _nil = Nil;
 
@@ -199,6 +189,26 @@ fn main() {
     inspect(load);
     inspect(unload);
 
+    // the same events through a bus, where every kind can have several handlers
+    let mut bus = event_bus::EventBus::new();
+    bus.subscribe(event_bus::EventKind::KeyPress, |event| {
+        inspect(event.clone());
+        event_bus::Propagation::Continue
+    });
+    bus.subscribe(event_bus::EventKind::Paste, |event| {
+        println!("paste blocked: {}", event);
+        event_bus::Propagation::Stop
+    });
+    bus.subscribe(event_bus::EventKind::Paste, |event| {
+        inspect(event.clone());
+        event_bus::Propagation::Continue
+    });
+    bus.dispatch(WebEvent::PageLoad);
+    bus.dispatch(WebEvent::KeyPress('y'));
+    bus.dispatch(WebEvent::Paste("from the bus".to_owned()));
+    bus.dispatch(WebEvent::Click { x: 5, y: 6 });
+    print!("recorded session:\n{}", bus.log());
+
     use crate::Status::{Poor, Rich};
     use crate::Work::*;
 
@@ -222,6 +232,15 @@ fn main() {
     println!("roses are #{:06x}", Color::Red as i32);
     println!("violets are #{:06x}", Color::Blue as i32);
 
+    // and back again, only values and names of real variants convert
+    println!("0xff0000 is {:?}", Color::try_from(0xff0000));
+    println!("7 is {:?}", Number::try_from(7u32));
+    println!("\"Green\" is {:?}", "Green".parse::<Color>());
+    for color in Color::iter() {
+        println!("{} is #{:06x}", color, color as i32);
+    }
+    println!("there are {} kinds of work", Work::COUNT);
+
     let mut list = List::new();
     list = list.prepend(1);
     list = list.prepend(2);
@@ -230,6 +249,19 @@ fn main() {
     println!("linked list has length: {}", list.len());
     println!("{}", list.stringify());
 
+    // `prepend` leaves the original list usable, both share the same nodes
+    let longer = list.prepend(4);
+    println!(
+        "{} shares its tail with {}",
+        longer.stringify(),
+        list.stringify()
+    );
+    let doubled = longer.map(|x| x * 2);
+    println!("doubled and reversed: {:?}", doubled.reverse());
+    let numbers: List<u32> = (1..=3).collect();
+    println!("appended: {:?}", numbers.append(&list));
+    println!("sum: {}", numbers.iter().sum::<u32>());
+
     let n = 16;
     println!("This is {}", LANGUAGE);
     println!("The threshold is {}", THRESHOLD);
@@ -0,0 +1,441 @@
+// color spaces and conversions between them
+// RGB components are 0..=255, hues are degrees 0..360, everything else is a percentage 0..=100.
+// every conversion goes through a normalised (r, g, b) triple of f64 in 0.0..=1.0, so the only
+// loss comes from rounding the result back to integer components.
+
+// the compiler provides a dead_code lint that will warn about unused functions,
+// the allow(dead_code) will disable the lint
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Red,
+    Blue,
+    Green,
+    RGB(u32, u32, u32),
+    HSV(u32, u32, u32),
+    HSL(u32, u32, u32),
+    CMY(u32, u32, u32),
+    CMYK(u32, u32, u32, u32),
+}
+
+// the space a color is expressed in, named colors count as RGB
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Space {
+    Rgb,
+    Hsv,
+    Hsl,
+    Cmy,
+    Cmyk,
+}
+
+impl Color {
+    pub fn space(self) -> Space {
+        match self {
+            Color::Red | Color::Blue | Color::Green | Color::RGB(..) => Space::Rgb,
+            Color::HSV(..) => Space::Hsv,
+            Color::HSL(..) => Space::Hsl,
+            Color::CMY(..) => Space::Cmy,
+            Color::CMYK(..) => Space::Cmyk,
+        }
+    }
+
+    // convert into any other space
+    pub fn to_space(self, space: Space) -> Color {
+        let (r, g, b) = self.to_unit_rgb();
+        match space {
+            Space::Rgb => {
+                let (r, g, b) = (byte(r), byte(g), byte(b));
+                Color::RGB(r, g, b)
+            }
+            Space::Hsv => {
+                let (h, s, v) = unit_rgb_to_hsv(r, g, b);
+                Color::HSV(degree(h), percent(s), percent(v))
+            }
+            Space::Hsl => {
+                let (h, s, l) = unit_rgb_to_hsl(r, g, b);
+                Color::HSL(degree(h), percent(s), percent(l))
+            }
+            Space::Cmy => Color::CMY(percent(1.0 - r), percent(1.0 - g), percent(1.0 - b)),
+            Space::Cmyk => {
+                let (c, m, y, k) = unit_rgb_to_cmyk(r, g, b);
+                Color::CMYK(percent(c), percent(m), percent(y), percent(k))
+            }
+        }
+    }
+
+    pub fn to_rgb(self) -> Color {
+        self.to_space(Space::Rgb)
+    }
+
+    pub fn to_hsv(self) -> Color {
+        self.to_space(Space::Hsv)
+    }
+
+    pub fn to_hsl(self) -> Color {
+        self.to_space(Space::Hsl)
+    }
+
+    pub fn to_cmy(self) -> Color {
+        self.to_space(Space::Cmy)
+    }
+
+    pub fn to_cmyk(self) -> Color {
+        self.to_space(Space::Cmyk)
+    }
+
+    // the 8-bit red, green and blue channels
+    pub fn rgb_bytes(self) -> (u8, u8, u8) {
+        let (r, g, b) = self.to_unit_rgb();
+        (byte(r) as u8, byte(g) as u8, byte(b) as u8)
+    }
+
+    // render as `#rrggbb`
+    pub fn to_hex(self) -> String {
+        let (r, g, b) = self.rgb_bytes();
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+
+    // out of range components are clamped, so a `Color` built by hand never panics here
+    fn to_unit_rgb(self) -> (f64, f64, f64) {
+        match self {
+            Color::Red => (1.0, 0.0, 0.0),
+            Color::Green => (0.0, 1.0, 0.0),
+            Color::Blue => (0.0, 0.0, 1.0),
+            Color::RGB(r, g, b) => (unit(r, 255), unit(g, 255), unit(b, 255)),
+            Color::HSV(h, s, v) => hsv_to_unit_rgb(f64::from(h % 360), unit(s, 100), unit(v, 100)),
+            Color::HSL(h, s, l) => hsl_to_unit_rgb(f64::from(h % 360), unit(s, 100), unit(l, 100)),
+            Color::CMY(c, m, y) => (1.0 - unit(c, 100), 1.0 - unit(m, 100), 1.0 - unit(y, 100)),
+            Color::CMYK(c, m, y, k) => {
+                let k = unit(k, 100);
+                (
+                    (1.0 - unit(c, 100)) * (1.0 - k),
+                    (1.0 - unit(m, 100)) * (1.0 - k),
+                    (1.0 - unit(y, 100)) * (1.0 - k),
+                )
+            }
+        }
+    }
+}
+
+fn unit(value: u32, max: u32) -> f64 {
+    f64::from(value.min(max)) / f64::from(max)
+}
+
+fn byte(value: f64) -> u32 {
+    (value * 255.0).round() as u32
+}
+
+fn percent(value: f64) -> u32 {
+    (value * 100.0).round() as u32
+}
+
+fn degree(value: f64) -> u32 {
+    value.round() as u32 % 360
+}
+
+// hue in degrees shared by HSV and HSL, together with the max, min and chroma of the channels
+fn hue(r: f64, g: f64, b: f64) -> (f64, f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+
+    let hue = if chroma == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * (((g - b) / chroma) % 6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+
+    let hue = if hue < 0.0 { hue + 360.0 } else { hue };
+    (hue, max, min, chroma)
+}
+
+fn unit_rgb_to_hsv(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let (h, max, _, chroma) = hue(r, g, b);
+    let s = if max == 0.0 { 0.0 } else { chroma / max };
+    (h, s, max)
+}
+
+fn unit_rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let (h, max, min, chroma) = hue(r, g, b);
+    let l = (max + min) / 2.0;
+    let s = if chroma == 0.0 {
+        0.0
+    } else {
+        chroma / (1.0 - (2.0 * l - 1.0).abs())
+    };
+    (h, s, l)
+}
+
+fn unit_rgb_to_cmyk(r: f64, g: f64, b: f64) -> (f64, f64, f64, f64) {
+    let k = 1.0 - r.max(g).max(b);
+    if k >= 1.0 {
+        return (0.0, 0.0, 0.0, 1.0);
+    }
+    (
+        (1.0 - r - k) / (1.0 - k),
+        (1.0 - g - k) / (1.0 - k),
+        (1.0 - b - k) / (1.0 - k),
+        k,
+    )
+}
+
+// place a chroma on the color wheel, `m` is added to every channel afterwards
+fn from_chroma(h: f64, chroma: f64, m: f64) -> (f64, f64, f64) {
+    let h = h / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    (r + m, g + m, b + m)
+}
+
+fn hsv_to_unit_rgb(h: f64, s: f64, v: f64) -> (f64, f64, f64) {
+    let chroma = v * s;
+    from_chroma(h, chroma, v - chroma)
+}
+
+fn hsl_to_unit_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    from_chroma(h, chroma, l - chroma / 2.0)
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Color::Red => write!(f, "red"),
+            Color::Green => write!(f, "green"),
+            Color::Blue => write!(f, "blue"),
+            Color::RGB(r, g, b) => write!(f, "rgb({}, {}, {})", r, g, b),
+            Color::HSV(h, s, v) => write!(f, "hsv({}, {}%, {}%)", h, s, v),
+            Color::HSL(h, s, l) => write!(f, "hsl({}, {}%, {}%)", h, s, l),
+            Color::CMY(c, m, y) => write!(f, "cmy({}%, {}%, {}%)", c, m, y),
+            Color::CMYK(c, m, y, k) => write!(f, "cmyk({}%, {}%, {}%, {}%)", c, m, y, k),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseColorError {
+    // neither a name, a hex code nor a known `space(...)` function
+    UnknownFormat(String),
+    // `hsl(1, 2)` and friends
+    WrongArity { expected: usize, found: usize },
+    // a component that is not a number, or has a `%` where none is allowed
+    InvalidComponent(String),
+    // a component above the maximum of its position
+    OutOfRange { value: u32, max: u32 },
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColorError::UnknownFormat(s) => write!(f, "unknown color format: {:?}", s),
+            ParseColorError::WrongArity { expected, found } => {
+                write!(f, "expected {} components, found {}", expected, found)
+            }
+            ParseColorError::InvalidComponent(s) => write!(f, "invalid color component: {:?}", s),
+            ParseColorError::OutOfRange { value, max } => {
+                write!(f, "color component {} is larger than {}", value, max)
+            }
+        }
+    }
+}
+
+impl Error for ParseColorError {}
+
+// parse one component, `percent` decides whether a trailing `%` is accepted
+fn component(s: &str, max: u32, percent: bool) -> Result<u32, ParseColorError> {
+    let digits = if percent {
+        s.strip_suffix('%').unwrap_or(s)
+    } else {
+        s
+    };
+    let value: u32 = digits
+        .parse()
+        .map_err(|_| ParseColorError::InvalidComponent(s.to_owned()))?;
+    if value > max {
+        return Err(ParseColorError::OutOfRange { value, max });
+    }
+    Ok(value)
+}
+
+fn parse_hex(hex: &str) -> Result<Color, ParseColorError> {
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ParseColorError::InvalidComponent(format!("#{}", hex)));
+    }
+    let channel = |i: usize| u32::from_str_radix(&hex[i..i + 2], 16).unwrap();
+    Ok(Color::RGB(channel(0), channel(2), channel(4)))
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    // accepts everything `Display` emits, plus `#rrggbb`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        match lower.as_str() {
+            "red" => return Ok(Color::Red),
+            "green" => return Ok(Color::Green),
+            "blue" => return Ok(Color::Blue),
+            _ => {}
+        }
+        if let Some(hex) = lower.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+
+        let unknown = || ParseColorError::UnknownFormat(s.to_owned());
+        let open = lower.find('(').ok_or_else(unknown)?;
+        let body = lower[open + 1..].strip_suffix(')').ok_or_else(unknown)?;
+        let parts: Vec<&str> = body.split(',').map(str::trim).collect();
+
+        // (max, percent allowed) for every component of the space
+        let layout: &[(u32, bool)] = match lower[..open].trim() {
+            "rgb" => &[(255, false); 3],
+            "hsv" | "hsl" => &[(359, false), (100, true), (100, true)],
+            "cmy" => &[(100, true); 3],
+            "cmyk" => &[(100, true); 4],
+            _ => return Err(unknown()),
+        };
+        if parts.len() != layout.len() {
+            return Err(ParseColorError::WrongArity {
+                expected: layout.len(),
+                found: parts.len(),
+            });
+        }
+
+        let mut values = [0; 4];
+        for (i, (part, &(max, percent))) in parts.iter().zip(layout).enumerate() {
+            values[i] = component(part, max, percent)?;
+        }
+        let [a, b, c, d] = values;
+
+        Ok(match lower[..open].trim() {
+            "rgb" => Color::RGB(a, b, c),
+            "hsv" => Color::HSV(a, b, c),
+            "hsl" => Color::HSL(a, b, c),
+            "cmy" => Color::CMY(a, b, c),
+            _ => Color::CMYK(a, b, c, d),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACES: [Space; 5] = [Space::Rgb, Space::Hsv, Space::Hsl, Space::Cmy, Space::Cmyk];
+
+    fn channel_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u8 {
+        let d = |x: u8, y: u8| x.abs_diff(y);
+        d(a.0, b.0).max(d(a.1, b.1)).max(d(a.2, b.2))
+    }
+
+    #[test]
+    fn test_known_conversions() {
+        assert_eq!(Color::Red.to_hsl(), Color::HSL(0, 100, 50));
+        assert_eq!(Color::Green.to_hsv(), Color::HSV(120, 100, 100));
+        assert_eq!(Color::Blue.to_cmyk(), Color::CMYK(100, 100, 0, 0));
+        assert_eq!(Color::RGB(0, 0, 0).to_cmyk(), Color::CMYK(0, 0, 0, 100));
+        assert_eq!(Color::HSL(120, 50, 40).to_rgb(), Color::RGB(51, 153, 51));
+        assert_eq!(Color::CMY(0, 100, 100).to_rgb(), Color::RGB(255, 0, 0));
+    }
+
+    #[test]
+    fn test_round_trip_through_every_space() {
+        // integer percentages quantise each channel to ~2.55 steps, so allow a small drift
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let rgb = Color::RGB(r, g, b);
+                    for &space in SPACES.iter() {
+                        let back = rgb.to_space(space).to_rgb();
+                        let distance = channel_distance(rgb.rgb_bytes(), back.rgb_bytes());
+                        assert!(
+                            distance <= 3,
+                            "{} -> {} -> {}",
+                            rgb,
+                            rgb.to_space(space),
+                            back
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_between_every_pair() {
+        let color = Color::RGB(122, 17, 40);
+        for &from in SPACES.iter() {
+            for &to in SPACES.iter() {
+                let back = color.to_space(from).to_space(to).to_rgb();
+                assert!(channel_distance(color.rgb_bytes(), back.rgb_bytes()) <= 3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_from_str_round_trip() {
+        let colors = [
+            Color::Red,
+            Color::RGB(122, 17, 40),
+            Color::HSV(200, 30, 70),
+            Color::HSL(120, 50, 40),
+            Color::CMY(10, 20, 30),
+            Color::CMYK(10, 20, 30, 40),
+        ];
+        for color in colors.iter() {
+            assert_eq!(color.to_string().parse::<Color>(), Ok(*color));
+        }
+    }
+
+    #[test]
+    fn test_parse_hsl_to_hex() {
+        let color: Color = "hsl(120, 50%, 40%)".parse().unwrap();
+        assert_eq!(color, Color::HSL(120, 50, 40));
+        assert_eq!(color.to_hex(), "#339933");
+        assert_eq!("#339933".parse::<Color>(), Ok(Color::RGB(51, 153, 51)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            "lab(1, 2, 3)".parse::<Color>(),
+            Err(ParseColorError::UnknownFormat("lab(1, 2, 3)".to_owned()))
+        );
+        assert_eq!(
+            "rgb(1, 2)".parse::<Color>(),
+            Err(ParseColorError::WrongArity {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "rgb(1, 2%, 3)".parse::<Color>(),
+            Err(ParseColorError::InvalidComponent("2%".to_owned()))
+        );
+        assert_eq!(
+            "hsl(360, 50%, 40%)".parse::<Color>(),
+            Err(ParseColorError::OutOfRange {
+                value: 360,
+                max: 359
+            })
+        );
+    }
+}
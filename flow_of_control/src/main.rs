@@ -1,3 +1,10 @@
+mod color;
+#[path = "../../functions/src/fizzbuzz.rs"]
+mod fizzbuzz;
+
+use color::Color;
+use fizzbuzz::FizzBuzz;
+
 fn main() {
     let n = 5;
 
@@ -63,15 +70,12 @@ fn main() {
 
     assert_eq!(result, 20);
 
+    let fizzbuzz = FizzBuzz::classic();
     let mut n = 1;
 
     while n < 101 {
-        if n % 15 == 0 {
-            println!("fizzbuzz");
-        } else if n % 3 == 0 {
-            println!("fizz")
-        } else if n % 5 == 0 {
-            println!("buzz");
+        if let Some(words) = fizzbuzz.words(n) {
+            println!("{}", words);
         } else {
             // println!("{}", n);
         }
@@ -79,12 +83,8 @@ fn main() {
     }
 
     for n in 1..101 {
-        if n % 15 == 0 {
-            println!("fizzbuzz");
-        } else if n % 3 == 0 {
-            println!("fizz");
-        } else if n % 5 == 0 {
-            println!("buzz");
+        if let Some(words) = fizzbuzz.words(n) {
+            println!("{}", words);
         } else {
             // println!("{}", n);
         }
@@ -169,6 +169,15 @@ fn main() {
         }
     }
 
+    // the same color in every other space
+    println!("{} is {}", color, color.to_hsv());
+    println!("{} is {}", color, color.to_hsl());
+    println!("{} is {}", color, color.to_cmy());
+    println!("{} is {}", color, color.to_cmyk());
+
+    let parsed: Color = "hsl(120, 50%, 40%)".parse().unwrap();
+    println!("{} is {}", parsed, parsed.to_hex());
+
     // pointers/ref
     // dereferencing uses *
     // destructuring uses &, ref and ref mut
@@ -326,17 +335,3 @@ enum Foo2 {
 fn age() -> u32 {
     15
 }
-
-// the compiler provides a dead_code lint that will warn about unused functions,
-// the allow(dead_code) will disable the lint
-#[allow(dead_code)]
-enum Color {
-    Red,
-    Blue,
-    Green,
-    RGB(u32, u32, u32),
-    HSV(u32, u32, u32),
-    HSL(u32, u32, u32),
-    CMY(u32, u32, u32),
-    CMYK(u32, u32, u32, u32),
-}
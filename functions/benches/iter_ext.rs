@@ -0,0 +1,218 @@
+// every adaptor of `IterExt` against the loop it replaces:
+//
+//     cargo bench --bench iter_ext
+//
+// both sides read the same pseudo random data and compute the same checksum, which is compared
+// so neither can be optimised away. `chunks` and `group_by` hand out a `Vec` per item, that
+// allocation is most of the difference to their loops; `windows` shifts one array along.
+
+// without a test harness the `#[test]`s of the module are left out, and their imports unused
+#[allow(unused_imports)]
+#[path = "../src/iter_ext.rs"]
+mod iter_ext;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use iter_ext::IterExt;
+
+const N: usize = 1_000_000;
+const RUNS: u32 = 10;
+
+// values below 4, so neighbours are equal often enough for `dedup_by_key` and `group_by`
+fn data() -> Vec<u64> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..N)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % 4
+        })
+        .collect()
+}
+
+// the fastest of `RUNS` runs, with the checksum they all agreed on
+fn time(data: &[u64], f: impl Fn(&[u64]) -> u64) -> (Duration, u64) {
+    let mut best = Duration::MAX;
+    let mut checksum = None;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let sum = f(black_box(data));
+        best = best.min(start.elapsed());
+        assert!(checksum.is_none() || checksum == Some(sum));
+        checksum = Some(sum);
+    }
+    (best, checksum.unwrap())
+}
+
+// an order dependent checksum
+fn mix(hash: u64, x: u64) -> u64 {
+    hash.wrapping_mul(31).wrapping_add(x)
+}
+
+fn compare(
+    name: &str,
+    data: &[u64],
+    adaptor: impl Fn(&[u64]) -> u64,
+    by_hand: impl Fn(&[u64]) -> u64,
+) {
+    let (adaptor_time, adaptor_sum) = time(data, adaptor);
+    let (by_hand_time, by_hand_sum) = time(data, by_hand);
+    assert_eq!(adaptor_sum, by_hand_sum, "{} disagrees with its loop", name);
+    println!(
+        "{:<18} adaptor {:>10.3?}  loop {:>10.3?}  ({:.2}x)",
+        name,
+        adaptor_time,
+        by_hand_time,
+        adaptor_time.as_secs_f64() / by_hand_time.as_secs_f64()
+    );
+}
+
+fn main() {
+    let data = data();
+
+    compare(
+        "chunks(8)",
+        &data,
+        |data| data.iter().chunks(8).map(|c| *c[c.len() - 1]).fold(0, mix),
+        |data| {
+            let mut hash = 0;
+            let mut i = 0;
+            while i < data.len() {
+                hash = mix(hash, data[(i + 8).min(data.len()) - 1]);
+                i += 8;
+            }
+            hash
+        },
+    );
+
+    compare(
+        "windows(3)",
+        &data,
+        |data| {
+            data.iter()
+                .windows::<3>()
+                .map(|[a, _, c]| a * c)
+                .fold(0, mix)
+        },
+        |data| {
+            let mut hash = 0;
+            for i in 2..data.len() {
+                hash = mix(hash, data[i - 2] * data[i]);
+            }
+            hash
+        },
+    );
+
+    compare(
+        "interleave",
+        &data,
+        |data| {
+            let (a, b) = data.split_at(data.len() / 2);
+            a.iter().interleave(b).fold(0, |h, &x| mix(h, x))
+        },
+        |data| {
+            let (a, b) = data.split_at(data.len() / 2);
+            let mut hash = 0;
+            for (x, y) in a.iter().zip(b) {
+                hash = mix(mix(hash, *x), *y);
+            }
+            for y in &b[a.len()..] {
+                hash = mix(hash, *y);
+            }
+            hash
+        },
+    );
+
+    compare(
+        "dedup_by_key",
+        &data,
+        |data| data.iter().dedup_by_key(|&&x| x).fold(0, |h, &x| mix(h, x)),
+        |data| {
+            let mut hash = 0;
+            let mut last = None;
+            for &x in data {
+                if last != Some(x) {
+                    last = Some(x);
+                    hash = mix(hash, x);
+                }
+            }
+            hash
+        },
+    );
+
+    compare(
+        "group_by",
+        &data,
+        |data| {
+            data.iter()
+                .group_by(|&&x| x)
+                .map(|(k, run)| k << 32 | run.len() as u64)
+                .fold(0, mix)
+        },
+        |data| {
+            let mut hash = 0;
+            let mut start = 0;
+            for i in 1..=data.len() {
+                if i == data.len() || data[i] != data[start] {
+                    hash = mix(hash, data[start] << 32 | (i - start) as u64);
+                    start = i;
+                }
+            }
+            hash
+        },
+    );
+
+    compare(
+        "intersperse",
+        &data,
+        |data| IterExt::intersperse(data.iter(), &7).fold(0, |h, &x| mix(h, x)),
+        |data| {
+            let mut hash = 0;
+            for (i, &x) in data.iter().enumerate() {
+                if i > 0 {
+                    hash = mix(hash, 7);
+                }
+                hash = mix(hash, x);
+            }
+            hash
+        },
+    );
+
+    compare(
+        "cartesian_product",
+        &data,
+        |data| {
+            let (a, b) = data.split_at(1000);
+            a.iter()
+                .cartesian_product(&b[..1000])
+                .fold(0, |h, (x, y)| mix(h, x ^ y))
+        },
+        |data| {
+            let (a, b) = data.split_at(1000);
+            let mut hash = 0;
+            for x in a {
+                for y in &b[..1000] {
+                    hash = mix(hash, x ^ y);
+                }
+            }
+            hash
+        },
+    );
+
+    compare(
+        "running_total",
+        &data,
+        |data| data.iter().copied().running_total().fold(0, mix),
+        |data| {
+            let mut hash = 0;
+            let mut total = 0;
+            for &x in data {
+                total += x;
+                hash = mix(hash, total);
+            }
+            hash
+        },
+    );
+}
@@ -0,0 +1,131 @@
+// play FizzBuzz with rules from the command line:
+//
+//     cargo run --bin fizzbuzz -- --rule 3=Fizz --rule 5=Buzz --rule 7=Bazz --from 7 --step 7
+//
+// without any `--rule` the classic fizz/buzz rules are used.
+
+#[path = "../fizzbuzz.rs"]
+mod fizzbuzz;
+
+use std::env;
+use std::process;
+
+use fizzbuzz::{FizzBuzz, Rule};
+
+const USAGE: &str = "usage: fizzbuzz [--rule DIVISOR=WORD]... [--from N] [--to N] [--step N]";
+
+struct Args {
+    rules: Vec<Rule>,
+    from: u32,
+    to: u32,
+    step: usize,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        rules: Vec::new(),
+        from: 1,
+        to: 100,
+        step: 1,
+    };
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+        let number = || format!("{} needs a number, got {:?}", flag, value);
+        match flag.as_str() {
+            "--rule" => parsed
+                .rules
+                .push(value.parse().map_err(|err| format!("{}", err))?),
+            "--from" => parsed.from = value.parse().map_err(|_| number())?,
+            "--to" => parsed.to = value.parse().map_err(|_| number())?,
+            "--step" => match value.parse() {
+                Ok(step) if step > 0 => parsed.step = step,
+                _ => return Err(number()),
+            },
+            _ => return Err(format!("unknown flag {}", flag)),
+        }
+    }
+    Ok(parsed)
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let mut game = if args.rules.is_empty() {
+        FizzBuzz::classic()
+    } else {
+        FizzBuzz::new()
+    };
+    for rule in args.rules {
+        game = game.add(rule);
+    }
+    for line in game.range(args.from..=args.to).step(args.step).iter() {
+        println!("{}", line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let args = parse(&[]).unwrap();
+        assert!(args.rules.is_empty());
+        assert_eq!((args.from, args.to, args.step), (1, 100, 1));
+    }
+
+    #[test]
+    fn test_valid_flags() {
+        let args = parse(&[
+            "--rule", "3=Fizz", "--rule", "7=Bazz", "--from", "7", "--to", "21", "--step", "7",
+        ])
+        .unwrap();
+        assert_eq!((args.from, args.to, args.step), (7, 21, 7));
+        let mut game = FizzBuzz::new();
+        for rule in args.rules {
+            game = game.add(rule);
+        }
+        assert_eq!(game.say(21), "FizzBazz");
+        assert_eq!(game.say(14), "Bazz");
+    }
+
+    #[test]
+    fn test_unknown_and_incomplete_flags() {
+        assert_eq!(parse(&["--by", "3"]).err().unwrap(), "unknown flag --by");
+        assert_eq!(parse(&["--to"]).err().unwrap(), "--to needs a value");
+    }
+
+    #[test]
+    fn test_bad_numbers() {
+        assert_eq!(
+            parse(&["--from", "x"]).err().unwrap(),
+            "--from needs a number, got \"x\""
+        );
+        assert_eq!(
+            parse(&["--to", "-1"]).err().unwrap(),
+            "--to needs a number, got \"-1\""
+        );
+        assert_eq!(
+            parse(&["--step", "0"]).err().unwrap(),
+            "--step needs a number, got \"0\""
+        );
+        assert!(parse(&["--rule", "Fizz"]).is_err());
+        assert!(parse(&["--rule", "3="]).is_err());
+        assert_eq!(
+            parse(&["--rule", "0=Fizz"]).err().unwrap(),
+            "invalid rule \"0=Fizz\", expected DIVISOR=WORD"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+// closure combinators: functions that take closures and return new ones.
+// every combinator asks for the weakest closure trait it can work with (`FnOnce` < `FnMut` <
+// `Fn` in what the caller has to promise) and returns the strongest one it can keep: composing
+// two `Fn`s gives an `Fn`, wrapping an `FnOnce` can at best give an `FnMut` that refuses to run
+// it a second time.
+
+// `f` after `g`: x -> f(g(x))
+pub fn compose<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(B) -> C,
+    G: Fn(A) -> B,
+{
+    move |x| f(g(x))
+}
+
+// `f` then `g`: x -> g(f(x)), reads in the order things happen
+pub fn pipe<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(A) -> B,
+    G: Fn(B) -> C,
+{
+    compose(g, f)
+}
+
+// fix the first argument, the captured `a` is cloned for every call
+pub fn partial<A, B, C, F>(f: F, a: A) -> impl Fn(B) -> C
+where
+    A: Clone,
+    F: Fn(A, B) -> C,
+{
+    move |b| f(a.clone(), b)
+}
+
+// (a, b) -> c as a -> b -> c
+pub fn curry<A, B, C, F>(f: F) -> impl Fn(A) -> Box<dyn Fn(B) -> C>
+where
+    A: Clone + 'static,
+    B: 'static,
+    C: 'static,
+    F: Fn(A, B) -> C + Clone + 'static,
+{
+    move |a| Box::new(partial(f.clone(), a))
+}
+
+// a -> b -> c as (a, b) -> c
+pub fn uncurry<A, B, C, F, G>(f: F) -> impl Fn(A, B) -> C
+where
+    F: Fn(A) -> G,
+    G: Fn(B) -> C,
+{
+    move |a, b| f(a)(b)
+}
+
+// swap the two arguments
+pub fn flip<A, B, C, F>(f: F) -> impl Fn(B, A) -> C
+where
+    F: Fn(A, B) -> C,
+{
+    move |b, a| f(a, b)
+}
+
+// run `f` on the first call only, later calls get `None` instead of a second run
+pub fn once<A, B, F>(f: F) -> impl FnMut(A) -> Option<B>
+where
+    F: FnOnce(A) -> B,
+{
+    let mut f = Some(f);
+    move |a| f.take().map(|f| f(a))
+}
+
+// call `f` and show its result to `side` before returning it, for logging or counting
+pub fn tap<A, B, F, S>(mut f: F, mut side: S) -> impl FnMut(A) -> B
+where
+    F: FnMut(A) -> B,
+    S: FnMut(&B),
+{
+    move |a| {
+        let b = f(a);
+        side(&b);
+        b
+    }
+}
+
+// calling any `Fn` with its arguments packed in a tuple, whatever its arity
+pub trait Apply<Args> {
+    type Output;
+
+    fn apply(&self, args: Args) -> Self::Output;
+}
+
+macro_rules! impl_apply {
+    ($($arg:ident),*) => {
+        impl<F, R, $($arg),*> Apply<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> R,
+        {
+            type Output = R;
+
+            #[allow(non_snake_case)]
+            fn apply(&self, ($($arg,)*): ($($arg,)*)) -> R {
+                self($($arg),*)
+            }
+        }
+    };
+}
+
+impl_apply!();
+impl_apply!(A);
+impl_apply!(A, B);
+impl_apply!(A, B, C);
+impl_apply!(A, B, C, D);
+impl_apply!(A, B, C, D, E);
+
+// `f` on every tuple of arguments, in order
+pub fn apply_all<F, Args>(f: &F, args: Vec<Args>) -> Vec<F::Output>
+where
+    F: Apply<Args>,
+{
+    args.into_iter().map(|args| f.apply(args)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_compose_and_pipe() {
+        let inc = |x: i32| x + 1;
+        let double = |x: i32| x * 2;
+        assert_eq!(compose(inc, double)(5), 11);
+        assert_eq!(pipe(inc, double)(5), 12);
+        let describe = pipe(|x: u8| x.count_ones(), |n| format!("{} ones", n));
+        assert_eq!(describe(0b1011), "3 ones");
+    }
+
+    #[test]
+    fn test_partial_curry_flip() {
+        let sub = |a: i32, b: i32| a - b;
+        assert_eq!(partial(sub, 10)(3), 7);
+        assert_eq!(flip(sub)(10, 3), -7);
+        assert_eq!(curry(sub)(10)(3), 7);
+        assert_eq!(uncurry(curry(sub))(10, 3), 7);
+
+        let greet = partial(
+            |greeting: String, name: &str| greeting + name,
+            "hi ".to_owned(),
+        );
+        // the captured `String` is cloned, so the closure can be called again
+        assert_eq!(greet("ann"), "hi ann");
+        assert_eq!(greet("bob"), "hi bob");
+    }
+
+    #[test]
+    fn test_apply_any_arity() {
+        assert_eq!((|| 1).apply(()), 1);
+        assert_eq!((|a: i32| -a).apply((4,)), -4);
+        assert_eq!(
+            (|a: u8, b: &str, c: bool, d: char, e: f32| format!("{}{}{}{}{}", a, b, c, d, e))
+                .apply((1, "b", true, 'd', 0.5)),
+            "1btrued0.5"
+        );
+        assert_eq!(
+            apply_all(&|a: u32, b: u32| a + b, vec![(1, 2), (3, 4)]),
+            vec![3, 7]
+        );
+    }
+
+    // `Fn` captures by reference: the captured value is still usable afterwards and the
+    // closure can be called any number of times
+    #[test]
+    fn test_fn_borrows() {
+        let haystack = [1, 2, 3];
+        let contains = |needle: i32| haystack.contains(&needle);
+        let not = |b: bool| !b;
+        let missing = pipe(contains, not);
+        assert!(missing(4));
+        assert!(!missing(2));
+        assert_eq!(haystack.len(), 3);
+    }
+
+    // `FnMut` captures by mutable reference: every call sees the changes of the one before
+    #[test]
+    fn test_fn_mut_mutates() {
+        let mut calls = 0;
+        let mut seen = Vec::new();
+        {
+            let mut square = tap(
+                |x: i32| {
+                    calls += 1;
+                    x * x
+                },
+                |y: &i32| seen.push(*y),
+            );
+            assert_eq!(square(3), 9);
+            assert_eq!(square(4), 16);
+        }
+        // the mutable borrows end with the closure
+        assert_eq!(calls, 2);
+        assert_eq!(seen, vec![9, 16]);
+    }
+
+    // `FnOnce` captures by value and is consumed by its call, `once` turns a second call
+    // into `None` instead of a compile error
+    #[test]
+    fn test_fn_once_moves() {
+        let shared = Rc::new("owned".to_owned());
+        let captured = Rc::clone(&shared);
+        let consume = move |suffix: &str| {
+            let s: String = Rc::try_unwrap(captured).unwrap_or_else(|rc| (*rc).clone());
+            s + suffix
+        };
+        assert_eq!(Rc::strong_count(&shared), 2);
+
+        let mut guarded = once(consume);
+        assert_eq!(guarded("!"), Some("owned!".to_owned()));
+        // the capture was moved into the call and dropped there
+        assert_eq!(Rc::strong_count(&shared), 1);
+        assert_eq!(guarded("?"), None);
+    }
+
+    #[test]
+    fn test_once_runs_side_effects_once() {
+        let runs = Cell::new(0);
+        let mut init = once(|()| runs.set(runs.get() + 1));
+        for _ in 0..3 {
+            init(());
+        }
+        assert_eq!(runs.get(), 1);
+    }
+}
@@ -0,0 +1,232 @@
+// FizzBuzz with the rules as data.
+// a rule pairs a test, either a divisor or any predicate, with a word. the words of every rule
+// a number passes are joined in the order the rules were added, a number no rule matches is
+// said as itself: `classic()` says "fizzbuzz" for 15 because 15 passes both of its rules.
+// flow_of_control and the `fizzbuzz` binary share this file.
+
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+enum Test {
+    Divisor(u32),
+    Predicate(Box<dyn Fn(u32) -> bool>),
+}
+
+pub struct Rule {
+    test: Test,
+    word: String,
+}
+
+impl Rule {
+    pub fn divisor(divisor: u32, word: &str) -> Rule {
+        Rule {
+            test: Test::Divisor(divisor),
+            word: word.to_owned(),
+        }
+    }
+
+    pub fn predicate<F>(predicate: F, word: &str) -> Rule
+    where
+        F: Fn(u32) -> bool + 'static,
+    {
+        Rule {
+            test: Test::Predicate(Box::new(predicate)),
+            word: word.to_owned(),
+        }
+    }
+
+    fn matches(&self, n: u32) -> bool {
+        match &self.test {
+            Test::Divisor(divisor) => is_divisible_by(n, *divisor),
+            Test::Predicate(predicate) => predicate(n),
+        }
+    }
+}
+
+fn is_divisible_by(lhs: u32, rhs: u32) -> bool {
+    if rhs == 0 {
+        return false;
+    }
+    lhs.is_multiple_of(rhs)
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseRuleError(String);
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid rule {:?}, expected DIVISOR=WORD", self.0)
+    }
+}
+
+impl Error for ParseRuleError {}
+
+// `7=Bazz`
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseRuleError(s.to_owned());
+        let (divisor, word) = s.split_once('=').ok_or_else(invalid)?;
+        let divisor = divisor.trim().parse().map_err(|_| invalid())?;
+        // `Rule::divisor(0, ..)` never matches, from text that can only be a typo
+        if divisor == 0 || word.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Rule::divisor(divisor, word))
+    }
+}
+
+pub struct FizzBuzz {
+    rules: Vec<Rule>,
+    range: RangeInclusive<u32>,
+    step: usize,
+}
+
+impl FizzBuzz {
+    // no rules yet, counting 1 to 100
+    pub fn new() -> FizzBuzz {
+        FizzBuzz {
+            rules: Vec::new(),
+            range: 1..=100,
+            step: 1,
+        }
+    }
+
+    // fizz for multiples of 3, buzz for multiples of 5
+    pub fn classic() -> FizzBuzz {
+        FizzBuzz::new().rule(3, "fizz").rule(5, "buzz")
+    }
+
+    pub fn rule(self, divisor: u32, word: &str) -> FizzBuzz {
+        self.add(Rule::divisor(divisor, word))
+    }
+
+    pub fn rule_fn<F>(self, predicate: F, word: &str) -> FizzBuzz
+    where
+        F: Fn(u32) -> bool + 'static,
+    {
+        self.add(Rule::predicate(predicate, word))
+    }
+
+    pub fn add(mut self, rule: Rule) -> FizzBuzz {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn range(mut self, range: RangeInclusive<u32>) -> FizzBuzz {
+        self.range = range;
+        self
+    }
+
+    // panics on a step of 0, like `Iterator::step_by`
+    pub fn step(mut self, step: usize) -> FizzBuzz {
+        assert!(step != 0, "step must not be 0");
+        self.step = step;
+        self
+    }
+
+    // the joined words of the rules `n` passes, `None` when there are none.
+    // borrowed when exactly one rule matches, so the common case does not allocate
+    pub fn words(&self, n: u32) -> Option<Cow<'_, str>> {
+        let mut matching = self.rules.iter().filter(|rule| rule.matches(n));
+        let first = matching.next()?;
+        let mut words = Cow::Borrowed(first.word.as_str());
+        for rule in matching {
+            words.to_mut().push_str(&rule.word);
+        }
+        Some(words)
+    }
+
+    // what to say for `n`: its words, or the number itself
+    pub fn say(&self, n: u32) -> Cow<'_, str> {
+        self.words(n).unwrap_or_else(|| Cow::Owned(n.to_string()))
+    }
+
+    // what to say for every number of the range, computed as it is consumed
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, str>> + '_ {
+        self.range
+            .clone()
+            .step_by(self.step)
+            .map(move |n| self.say(n))
+    }
+}
+
+impl Default for FizzBuzz {
+    fn default() -> Self {
+        FizzBuzz::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic() {
+        let game = FizzBuzz::classic().range(1..=15);
+        let lines: Vec<Cow<str>> = game.iter().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz",
+                "13", "14", "fizzbuzz"
+            ]
+        );
+        assert_eq!(FizzBuzz::classic().iter().count(), 100);
+    }
+
+    #[test]
+    fn test_rules_join_in_order() {
+        let game = FizzBuzz::new()
+            .rule(5, "Buzz")
+            .rule(3, "Fizz")
+            .rule(7, "Bazz")
+            .rule(0, "Never");
+        assert_eq!(game.say(15), "BuzzFizz");
+        assert_eq!(game.say(105), "BuzzFizzBazz");
+        assert_eq!(game.say(0), "BuzzFizzBazz");
+        assert_eq!(game.words(11), None);
+    }
+
+    #[test]
+    fn test_predicates() {
+        let game = FizzBuzz::classic().rule_fn(|n| n.to_string().contains('3'), "!");
+        assert_eq!(game.say(13), "!");
+        assert_eq!(game.say(30), "fizzbuzz!");
+        assert_eq!(game.say(22), "22");
+    }
+
+    #[test]
+    fn test_range_and_step() {
+        let game = FizzBuzz::classic().range(10..=30).step(4);
+        let lines: Vec<Cow<str>> = game.iter().collect();
+        assert_eq!(lines, vec!["buzz", "14", "fizz", "22", "26", "fizzbuzz"]);
+        let (start, end) = (5, 1);
+        assert_eq!(FizzBuzz::classic().range(start..=end).iter().count(), 0);
+    }
+
+    #[test]
+    fn test_only_combined_words_allocate() {
+        let game = FizzBuzz::classic();
+        assert!(matches!(game.say(3), Cow::Borrowed("fizz")));
+        assert!(matches!(game.say(15), Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule: Rule = "7=Bazz".parse().unwrap();
+        assert_eq!(FizzBuzz::new().add(rule).say(14), "Bazz");
+        for bad in ["7", "x=Bazz", "7=", "-1=No", "0=Fizz"].iter() {
+            assert_eq!(
+                bad.parse::<Rule>().err(),
+                Some(ParseRuleError(bad.to_string()))
+            );
+        }
+    }
+}
@@ -0,0 +1,556 @@
+// iterator adaptors that std lacks, for any `Iterator` through the `IterExt` trait.
+// every adaptor reports a `size_hint` that is as tight as it can be without consuming
+// anything: the lower and upper bounds of the inner iterator are carried through the same
+// arithmetic as the items themselves.
+// benches/iter_ext.rs includes this file too: `cargo bench` times every adaptor against the
+// loop it replaces.
+#![allow(dead_code)]
+
+use std::convert::TryInto;
+use std::iter::{Peekable, Scan};
+use std::ops::Add;
+
+pub trait IterExt: Iterator + Sized {
+    // items in `Vec`s of `n`, the last one shorter if the items run out
+    fn chunks(self, n: usize) -> Chunks<Self> {
+        assert!(n > 0, "chunks of size 0");
+        Chunks { iter: self, n }
+    }
+
+    // every run of `N` consecutive items, like `slice::windows` but for any iterator.
+    // the window is an array that is shifted along, so no window allocates: `windows::<3>()`
+    fn windows<const N: usize>(self) -> Windows<Self, N>
+    where
+        Self::Item: Clone,
+    {
+        assert!(N > 0, "windows of size 0");
+        Windows {
+            iter: self,
+            window: None,
+        }
+    }
+
+    // one item of `self`, one of `other`, ..., then the rest of whichever is longer
+    fn interleave<J>(self, other: J) -> Interleave<Self, J::IntoIter>
+    where
+        J: IntoIterator<Item = Self::Item>,
+    {
+        Interleave {
+            a: self,
+            b: other.into_iter(),
+            next_b: false,
+        }
+    }
+
+    // drop every item whose key is the same as the item before it
+    fn dedup_by_key<K, F>(self, key: F) -> DedupByKey<Self, K, F>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        DedupByKey {
+            iter: self,
+            key,
+            last: None,
+        }
+    }
+
+    // runs of consecutive items with the same key, with that key
+    fn group_by<K, F>(self, key: F) -> GroupBy<Self, K, F>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        GroupBy {
+            iter: self,
+            key,
+            pending: None,
+        }
+    }
+
+    // `sep` between every two items.
+    // std has an unstable `Iterator::intersperse`, calling this one as a method warns about the
+    // name collision, so call it as `IterExt::intersperse(iter, sep)`
+    fn intersperse(self, sep: Self::Item) -> Intersperse<Self>
+    where
+        Self::Item: Clone,
+    {
+        Intersperse {
+            iter: self.peekable(),
+            sep,
+            sep_next: false,
+        }
+    }
+
+    // every pair of an item of `self` with an item of `other`, `other` is cloned to start
+    // again for every item of `self`
+    fn cartesian_product<J>(self, other: J) -> CartesianProduct<Self, J::IntoIter>
+    where
+        Self::Item: Clone,
+        J: IntoIterator,
+        J::IntoIter: Clone,
+    {
+        let b = other.into_iter();
+        CartesianProduct {
+            a: self,
+            current: None,
+            b_rest: b.clone(),
+            b,
+        }
+    }
+
+    // the sum of all items so far, after every item
+    fn running_total(self) -> RunningTotal<Self>
+    where
+        Self::Item: Add<Output = Self::Item> + Default + Copy,
+    {
+        self.scan(Self::Item::default(), add_to_total)
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+// `(lo, hi)` of `n` items turned into `(f(lo), f(hi))`
+fn map_hint(
+    (lo, hi): (usize, Option<usize>),
+    f: impl Fn(usize) -> usize,
+) -> (usize, Option<usize>) {
+    (f(lo), hi.map(f))
+}
+
+// the sum of two hints, `None` when the upper bound overflows
+fn add_hints(a: (usize, Option<usize>), b: (usize, Option<usize>)) -> (usize, Option<usize>) {
+    let hi = match (a.1, b.1) {
+        (Some(x), Some(y)) => x.checked_add(y),
+        _ => None,
+    };
+    (a.0.saturating_add(b.0), hi)
+}
+
+pub struct Chunks<I> {
+    iter: I,
+    n: usize,
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        let first = self.iter.next()?;
+        let mut chunk = Vec::with_capacity(self.n);
+        chunk.push(first);
+        while chunk.len() < self.n {
+            match self.iter.next() {
+                Some(item) => chunk.push(item),
+                None => break,
+            }
+        }
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.n;
+        map_hint(self.iter.size_hint(), |len| len.div_ceil(n))
+    }
+}
+
+pub struct Windows<I: Iterator, const N: usize> {
+    iter: I,
+    // `None` until the first window is full
+    window: Option<[I::Item; N]>,
+}
+
+impl<I, const N: usize> Iterator for Windows<I, N>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<[I::Item; N]> {
+        match &mut self.window {
+            Some(window) => {
+                let item = self.iter.next()?;
+                // shifting by swaps beats `rotate_left` for the small windows this is for
+                for i in 1..N {
+                    window.swap(i - 1, i);
+                }
+                window[N - 1] = item;
+            }
+            None => {
+                // the only allocation, once: std has no stable way to fill an array that
+                // may run out of items
+                let first: Vec<I::Item> = self.iter.by_ref().take(N).collect();
+                self.window = Some(first.try_into().ok()?);
+            }
+        }
+        self.window.clone()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // every item after the first full window makes one more window
+        let before_first = if self.window.is_some() { 0 } else { N - 1 };
+        map_hint(self.iter.size_hint(), |len| {
+            len.saturating_sub(before_first)
+        })
+    }
+}
+
+pub struct Interleave<I, J> {
+    a: I,
+    b: J,
+    next_b: bool,
+}
+
+impl<I, J> Iterator for Interleave<I, J>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.next_b = !self.next_b;
+        if self.next_b {
+            self.a.next().or_else(|| self.b.next())
+        } else {
+            self.b.next().or_else(|| self.a.next())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        add_hints(self.a.size_hint(), self.b.size_hint())
+    }
+}
+
+pub struct DedupByKey<I, K, F> {
+    iter: I,
+    key: F,
+    last: Option<K>,
+}
+
+impl<I, K, F> Iterator for DedupByKey<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        for item in self.iter.by_ref() {
+            let key = (self.key)(&item);
+            if self.last.as_ref() != Some(&key) {
+                self.last = Some(key);
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.iter.size_hint();
+        // after the first item every other one could be a duplicate
+        let lo = if self.last.is_none() { lo.min(1) } else { 0 };
+        (lo, hi)
+    }
+}
+
+pub struct GroupBy<I: Iterator, K, F> {
+    iter: I,
+    key: F,
+    // the first item of the next group, read while looking for the end of the last one
+    pending: Option<(K, I::Item)>,
+}
+
+impl<I, K, F> Iterator for GroupBy<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<(K, Vec<I::Item>)> {
+        let (key, first) = match self.pending.take() {
+            Some(pending) => pending,
+            None => {
+                let item = self.iter.next()?;
+                ((self.key)(&item), item)
+            }
+        };
+        let mut group = vec![first];
+        for item in self.iter.by_ref() {
+            let next_key = (self.key)(&item);
+            if next_key != key {
+                self.pending = Some((next_key, item));
+                break;
+            }
+            group.push(item);
+        }
+        Some((key, group))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.pending.is_some() as usize;
+        let (lo, hi) = add_hints(self.iter.size_hint(), (pending, Some(pending)));
+        // all the items could be one group
+        (lo.min(1), hi)
+    }
+}
+
+pub struct Intersperse<I: Iterator> {
+    iter: Peekable<I>,
+    sep: I::Item,
+    sep_next: bool,
+}
+
+impl<I> Iterator for Intersperse<I>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        if self.sep_next && self.iter.peek().is_some() {
+            self.sep_next = false;
+            Some(self.sep.clone())
+        } else {
+            self.sep_next = true;
+            self.iter.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // `len` items still need `len - 1` separators, or `len` when one is due before the next
+        let sep_next = self.sep_next;
+        map_hint(self.iter.size_hint(), |len| {
+            if sep_next {
+                len.saturating_mul(2)
+            } else {
+                len.saturating_add(len.saturating_sub(1))
+            }
+        })
+    }
+}
+
+pub struct CartesianProduct<I: Iterator, J> {
+    a: I,
+    current: Option<I::Item>,
+    // `b` as it was at the start, to start again from
+    b: J,
+    b_rest: J,
+}
+
+impl<I, J> Iterator for CartesianProduct<I, J>
+where
+    I: Iterator,
+    I::Item: Clone,
+    J: Iterator + Clone,
+{
+    type Item = (I::Item, J::Item);
+
+    fn next(&mut self) -> Option<(I::Item, J::Item)> {
+        loop {
+            if let Some(a) = &self.current {
+                if let Some(b) = self.b_rest.next() {
+                    return Some((a.clone(), b));
+                }
+            }
+            self.current = Some(self.a.next()?);
+            self.b_rest = self.b.clone();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lo, a_hi) = self.a.size_hint();
+        let (b_lo, b_hi) = self.b.size_hint();
+        let rest = if self.current.is_some() {
+            self.b_rest.size_hint()
+        } else {
+            (0, Some(0))
+        };
+        let lo = a_lo.saturating_mul(b_lo);
+        let hi = match (a_hi, b_hi) {
+            (Some(a), Some(b)) => a.checked_mul(b),
+            _ => None,
+        };
+        add_hints((lo, hi), rest)
+    }
+}
+
+pub type RunningTotal<I> = Scan<
+    I,
+    <I as Iterator>::Item,
+    fn(&mut <I as Iterator>::Item, <I as Iterator>::Item) -> Option<<I as Iterator>::Item>,
+>;
+
+fn add_to_total<T: Add<Output = T> + Copy>(total: &mut T, x: T) -> Option<T> {
+    *total = *total + x;
+    Some(*total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // drives `iter` to the end, checking before every item that the hint brackets the number
+    // of items that are really left
+    fn check_size_hint<I: Iterator>(mut iter: I) -> Vec<I::Item> {
+        let mut hints = vec![iter.size_hint()];
+        let mut items = Vec::new();
+        while let Some(item) = iter.next() {
+            items.push(item);
+            hints.push(iter.size_hint());
+        }
+        for (seen, (lo, hi)) in hints.into_iter().enumerate() {
+            let left = items.len() - seen;
+            assert!(lo <= left, "lower bound {} > {} left", lo, left);
+            assert!(
+                hi.is_none_or(|hi| left <= hi),
+                "upper bound {:?} < {}",
+                hi,
+                left
+            );
+        }
+        items
+    }
+
+    // for inner iterators that know their length the hint has to be exact
+    fn check_exact_size_hint<I: Iterator>(mut iter: I) -> Vec<I::Item> {
+        let mut hints = vec![iter.size_hint()];
+        let mut items = Vec::new();
+        while let Some(item) = iter.next() {
+            items.push(item);
+            hints.push(iter.size_hint());
+        }
+        for (seen, hint) in hints.into_iter().enumerate() {
+            let left = items.len() - seen;
+            assert_eq!(hint, (left, Some(left)), "after {} items", seen);
+        }
+        items
+    }
+
+    #[test]
+    fn test_chunks() {
+        let chunks = check_exact_size_hint((1..=7).chunks(3));
+        assert_eq!(chunks, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+        assert!(check_size_hint((0..0).chunks(2)).is_empty());
+        // filter only knows an upper bound
+        let evens = check_size_hint((0..10).filter(|x| x % 2 == 0).chunks(4));
+        assert_eq!(evens, vec![vec![0, 2, 4, 6], vec![8]]);
+    }
+
+    #[test]
+    fn test_windows() {
+        let windows = check_exact_size_hint(
+            "abcd"
+                .chars()
+                .collect::<Vec<_>>()
+                .into_iter()
+                .windows::<2>(),
+        );
+        assert_eq!(windows, vec![['a', 'b'], ['b', 'c'], ['c', 'd']]);
+        assert_eq!((0..3).windows::<3>().collect::<Vec<_>>(), vec![[0, 1, 2]]);
+        assert!(check_exact_size_hint((0..2).windows::<3>()).is_empty());
+        assert_eq!(
+            check_exact_size_hint((0..5).windows::<1>()),
+            vec![[0], [1], [2], [3], [4]]
+        );
+
+        fn count<const N: usize>() -> usize {
+            check_size_hint((0..4).filter(|_| true).windows::<N>()).len()
+        }
+        assert_eq!(
+            [
+                count::<1>(),
+                count::<2>(),
+                count::<3>(),
+                count::<4>(),
+                count::<5>()
+            ],
+            [4, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn test_interleave() {
+        let mixed = check_exact_size_hint((1..4).interleave(vec![10, 20, 30, 40, 50]));
+        assert_eq!(mixed, vec![1, 10, 2, 20, 3, 30, 40, 50]);
+        let mixed = check_exact_size_hint((1..6).interleave(Some(10)));
+        assert_eq!(mixed, vec![1, 10, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_dedup_by_key() {
+        let words = ["apple", "avocado", "banana", "blueberry", "apricot"];
+        let firsts = check_size_hint(words.iter().dedup_by_key(|w| w.as_bytes()[0]));
+        assert_eq!(firsts, vec![&"apple", &"banana", &"apricot"]);
+        assert_eq!(
+            check_size_hint([1, 1, 1].iter().dedup_by_key(|&&x| x)).len(),
+            1
+        );
+        assert!(check_size_hint((0..0).dedup_by_key(|&x| x)).is_empty());
+    }
+
+    #[test]
+    fn test_group_by() {
+        let runs = check_size_hint([1, 3, 5, 2, 4, 7, 9, 9].iter().group_by(|&&x| x % 2));
+        assert_eq!(
+            runs,
+            vec![
+                (1, vec![&1, &3, &5]),
+                (0, vec![&2, &4]),
+                (1, vec![&7, &9, &9]),
+            ]
+        );
+        let letters: Vec<_> = "aabccc"
+            .chars()
+            .group_by(|&c| c)
+            .map(|(c, run)| (c, run.len()))
+            .collect();
+        assert_eq!(letters, vec![('a', 2), ('b', 1), ('c', 3)]);
+    }
+
+    #[test]
+    fn test_intersperse() {
+        let joined: String =
+            check_exact_size_hint(IterExt::intersperse(["a", "b", "c"].iter().copied(), ", "))
+                .concat();
+        assert_eq!(joined, "a, b, c");
+        assert_eq!(
+            check_exact_size_hint(IterExt::intersperse(0..1, 9)),
+            vec![0]
+        );
+        assert!(check_exact_size_hint(IterExt::intersperse(0..0, 9)).is_empty());
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let pairs = check_exact_size_hint((0..3).cartesian_product(['x', 'y'].iter().copied()));
+        assert_eq!(
+            pairs,
+            vec![(0, 'x'), (0, 'y'), (1, 'x'), (1, 'y'), (2, 'x'), (2, 'y')]
+        );
+        assert!(check_exact_size_hint((0..3).cartesian_product(0..0)).is_empty());
+        assert!(check_exact_size_hint((0..0).cartesian_product(0..3)).is_empty());
+    }
+
+    #[test]
+    fn test_running_total() {
+        let totals = check_size_hint([1, 2, 3, 4].iter().copied().running_total());
+        assert_eq!(totals, vec![1, 3, 6, 10]);
+        let odd_squares: Vec<u32> = (0..)
+            .map(|n| n * n)
+            .take_while(|&n| n < 100)
+            .filter(|n| n % 2 == 1)
+            .running_total()
+            .collect();
+        assert_eq!(odd_squares, vec![1, 10, 35, 84, 165]);
+        assert_eq!(
+            [0.5, 0.25].iter().copied().running_total().last(),
+            Some(0.75)
+        );
+    }
+}
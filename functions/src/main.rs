@@ -1,6 +1,22 @@
 // allow assign a never typ
 //#![feature(never_type)]
 
+mod combinators;
+// the Fibonacci iterator from traits, for memo.rs to race against
+#[path = "../../traits/src/fibonacci.rs"]
+mod fibonacci;
+mod fizzbuzz;
+mod iter_ext;
+mod memo;
+mod transform;
+
+use combinators::{apply_all, compose, curry, flip, once, partial, pipe, tap, uncurry, Apply};
+use fizzbuzz::FizzBuzz;
+use iter_ext::IterExt;
+use memo::{Memo, RecursiveMemo, SyncMemo};
+use std::time::Instant;
+use transform::Transform;
+
 fn main() {
     fizzbuzz(100);
 
@@ -20,6 +36,37 @@ fn main() {
     println!("square: p1: {:?}, p2: {:?}", square.p1, square.p2);
     println!("square area: {}", square.area());
 
+    // rotating the square about its own centre turns it into a diamond
+    let about_centre = Transform::translate(-2.5, -2.5)
+        .then(&Transform::rotate(std::f64::consts::FRAC_PI_4))
+        .then(&Transform::translate(2.5, 2.5));
+    let diamond = square.transform(&about_centre);
+    println!("diamond: {:?}", diamond.corners);
+    println!(
+        "diamond area: {:.3}, perimeter: {:.3}",
+        diamond.area(),
+        diamond.perimeter()
+    );
+    if let Some(undo) = about_centre.inverse() {
+        let back = diamond.transform(&undo).bounding_box();
+        println!(
+            "rotated back: {:.3} x {:.3}",
+            back.p2.x - back.p1.x,
+            back.p2.y - back.p1.y
+        );
+    }
+    let stretch = Transform::scale(2.0, 0.5);
+    println!(
+        "stretching keeps the area: {}",
+        stretch.determinant() == 1.0
+    );
+    let sheared = rectangle.transform(&Transform::shear(0.5, 0.0));
+    println!(
+        "sheared rectangle area: {}, perimeter: {:.3}",
+        sheared.area(),
+        sheared.perimeter()
+    );
+
     let pair = Pair(Box::new(1), Box::new(2));
     pair.destroy();
     // pair.destroy(); // has already moved and can not use again
@@ -147,6 +194,109 @@ fn main() {
     let fn_append = create_fn_append();
     println!("a + b = {}", fn_append(a, b));
 
+    // combinators build new closures out of existing ones
+    let add_one_then_double = pipe(|x: u32| x + 1, |x| x * 2);
+    let double_then_add_one = compose(|x: u32| x + 1, |x: u32| x * 2);
+    println!(
+        "(3 + 1) * 2 = {}, 3 * 2 + 1 = {}",
+        add_one_then_double(3),
+        double_then_add_one(3)
+    );
+    let add_ten = partial(function_add, 10);
+    println!("10 + 5 = {}", add_ten(5));
+    println!("curried 10 + 5 = {}", curry(function_add)(10)(5));
+    println!("uncurried 10 + 5 = {}", uncurry(curry(function_add))(10, 5));
+    let append = |a: String, b: &str| a + b;
+    println!(
+        "flipped append: {}",
+        flip(append)("world", "hello ".to_owned())
+    );
+    let mut launch = once(|target: &str| format!("launched at {}", target));
+    println!("{:?} then {:?}", launch("moon"), launch("mars"));
+    let mut log = tap(|x: u32| x * x, |y| println!("squared to {}", y));
+    log(7);
+    println!("1 + 2 = {}", function_add.apply((1, 2)));
+    println!(
+        "1 + 2, 3 + 4 = {:?}",
+        apply_all(&function_add, vec![(1, 2), (3, 4)])
+    );
+
+    // a memo runs a pure closure once per argument, optionally keeping only the latest results
+    let mut square = Memo::new(|x: u64| x * x);
+    let mut bounded = Memo::with_capacity(|x: u64| x * x, 2);
+    println!("nothing cached yet: {}", square.is_empty());
+    for x in [3, 3, 4, 3, 5, 4].iter() {
+        square.get(*x);
+        bounded.get(*x);
+    }
+    println!(
+        "squares: {} hits, {} misses, {} misses when keeping {} results",
+        square.hits(),
+        square.misses(),
+        bounded.misses(),
+        bounded.len()
+    );
+
+    // random access: the memo computes every Fibonacci number once, the iterator from traits
+    // (1, 2, 3, 5, ... so fib(n) is its item n - 2) walks up to every index asked for again.
+    // it is u32, so the indices stop at fib(46); walks that short cost less than hashing the key,
+    // so the memo wins on steps taken, not on time
+    let fibonacci = |fib: &mut dyn FnMut(u64) -> u64, n: u64| {
+        if n < 2 {
+            n
+        } else {
+            fib(n - 1).wrapping_add(fib(n - 2))
+        }
+    };
+    let mut fib = RecursiveMemo::new(fibonacci);
+    let indices: Vec<u64> = (0..200_000u64).map(|i| 2 + i * 7919 % 45).collect();
+    let start = Instant::now();
+    let memo_sum = indices
+        .iter()
+        .fold(0u64, |sum, &n| sum.wrapping_add(fib.get(n)));
+    let memo_time = start.elapsed();
+    let start = Instant::now();
+    let iter_sum = indices.iter().fold(0u64, |sum, &n| {
+        let nth = fibonacci::fibonacci().nth(n as usize - 2).unwrap();
+        sum.wrapping_add(nth.into())
+    });
+    let iter_time = start.elapsed();
+    let iter_steps: u64 = indices.iter().map(|n| n - 1).sum();
+    assert_eq!(memo_sum, iter_sum);
+    println!(
+        "fibonacci: memo {:?} ({} hits, {} misses), iterator {:?} ({} steps)",
+        memo_time,
+        fib.hits(),
+        fib.misses(),
+        iter_time,
+        iter_steps
+    );
+
+    // keeping only the last three results is enough to compute the next one
+    let mut recent = RecursiveMemo::with_capacity(fibonacci, 3);
+    println!(
+        "fib(90) mod 2^64 = {} with {} misses",
+        recent.get(90),
+        recent.misses()
+    );
+
+    // the sync memo is shared between threads by reference
+    let factorial = SyncMemo::new(|n: u64| (1..=n).product::<u64>());
+    let popcount = SyncMemo::with_capacity(|n: u64| n.count_ones(), 2);
+    std::thread::scope(|s| {
+        for t in 0..3 {
+            let factorial = &factorial;
+            let popcount = &popcount;
+            s.spawn(move || popcount.get(factorial.get(10 + t)));
+        }
+    });
+    println!(
+        "10! = {}, {} hits, {} misses",
+        factorial.get(10),
+        factorial.hits(),
+        factorial.misses()
+    );
+
     let vec1 = vec![1, 2, 3];
     let vec2 = vec![4, 5, 6];
     // Iterator::any return predicate if any item satisfied closure
@@ -196,6 +346,47 @@ fn main() {
         .fold(0, |acc, n_squared| acc + n_squared); // sum them
     println!("functional style: {}", sum_of_squared_odd_numbers);
 
+    // the same squares through the adaptors of IterExt
+    let odd_squares = (0..)
+        .map(|n| n * n)
+        .take_while(|&n_squared| n_squared < upper)
+        .filter(|&n_squared| is_odd(n_squared));
+    println!(
+        "running totals: {:?}",
+        odd_squares.clone().running_total().collect::<Vec<u32>>()
+    );
+    for row in odd_squares.clone().chunks(6) {
+        println!("  {:?}", row);
+    }
+    let gaps: Vec<u32> = odd_squares
+        .clone()
+        .windows::<2>()
+        .map(|[a, b]| b - a)
+        .collect();
+    println!("gaps between neighbours: {:?}", gaps);
+    let by_digits: Vec<(usize, usize)> = odd_squares
+        .clone()
+        .group_by(|n| n.to_string().len())
+        .map(|(digits, run)| (digits, run.len()))
+        .collect();
+    println!("(digits, how many): {:?}", by_digits);
+    let last_digits: Vec<u32> = odd_squares
+        .clone()
+        .map(|n| n % 10)
+        .dedup_by_key(|&d| d)
+        .collect();
+    println!("last digits without repeats: {:?}", last_digits);
+    let listed: String = IterExt::intersperse(
+        odd_squares.clone().take(5).map(|n| n.to_string()),
+        " + ".to_owned(),
+    )
+    .collect();
+    println!("{} = {}", listed, odd_squares.clone().take(5).sum::<u32>());
+    let mixed: Vec<u32> = odd_squares.clone().take(3).interleave(vec![0, 0]).collect();
+    println!("interleaved with zeros: {:?}", mixed);
+    let pairs = odd_squares.take(3).cartesian_product(1..=2);
+    println!("pairs: {:?}", pairs.collect::<Vec<_>>());
+
     // foo();
     let a: () = some_fn();
     println!("This function returns and you can see this line");
@@ -240,26 +431,25 @@ fn is_odd(n: u32) -> bool {
 // this can be done via boxing.
 // Fn: normal
 // FnMut: normal
-// FnOnce: there are some unusual things here, so the FnBox type is currently needed, and is unstable
-// this is expected to change in future
+// FnOnce: normal, a `Box<dyn FnOnce()>` can be called directly since Rust 1.35
 
 // Beyond this, the move keyword must be used for returning closure, because any captures by reference
 // would by dropped as soon as the function existed.
-fn create_fn() -> Box<Fn()> {
+fn create_fn() -> Box<dyn Fn()> {
     let text = "Fn".to_owned();
     Box::new(move || println!("This is a: {}", text))
 }
 
-fn create_fnmut() -> Box<FnMut()> {
+fn create_fnmut() -> Box<dyn FnMut()> {
     let text = "FnMut".to_owned();
     Box::new(move || println!("This is a: {}", text))
 }
 
-fn create_fn_add() -> Box<Fn(u32, u32) -> u32> {
+fn create_fn_add() -> Box<dyn Fn(u32, u32) -> u32> {
     Box::new(move |a, b| a + b)
 }
 
-fn create_fn_append() -> Box<FnOnce(String, String) -> String> {
+fn create_fn_append() -> Box<dyn FnOnce(String, String) -> String> {
     Box::new(move |a, b| {
         let mut s = a;
         s.push_str(b.as_str());
@@ -314,7 +504,7 @@ where
 }
 
 // methods are functions attached to objects
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 struct Point {
     x: f64,
     y: f64,
@@ -372,27 +562,12 @@ impl Pair {
 // functions that "don't' return a value, actually return the unit type `()`
 // (in Rust everything is type/repression?)
 fn fizzbuzz(n: u32) -> () {
-    if is_divisible_by(n, 15) {
-        println!("fizzbuzz");
-    } else if is_divisible_by(n, 3) {
-        println!("fizz");
-    } else if is_divisible_by(n, 5) {
-        println!("buzz");
-    } else {
-        println!("{}", n);
-    }
-}
-
-fn is_divisible_by(lhs: u32, rhs: u32) -> bool {
-    if rhs == 0 {
-        return false;
-    }
-    lhs % rhs == 0
+    println!("{}", FizzBuzz::classic().say(n));
 }
 
 // when a fn returns `()`, then return type can be omitted from the signature
 fn fizzbuzz_to(n: u32) {
-    for n in 1..=n {
-        fizzbuzz(n);
+    for line in FizzBuzz::classic().range(1..=n).iter() {
+        println!("{}", line);
     }
 }
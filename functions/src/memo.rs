@@ -0,0 +1,367 @@
+// memoization for pure closures: the result for every argument is computed once and then
+// served from a `HashMap`.
+// `Memo` wraps an `Fn(K) -> V`, `RecursiveMemo` a function that calls itself through the cache
+// (so memoized Fibonacci is linear), and `SyncMemo` shares its cache between threads behind a
+// `Mutex`. all three can be bounded to a capacity, the least recently used entry is evicted
+// first, and count their hits and misses.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::Mutex;
+
+// the cache the three wrappers share
+struct Cache<K, V> {
+    // every entry with the tick it was last used at
+    entries: HashMap<K, (V, u64)>,
+    // the same ticks in order, so the least recently used key is the first one
+    recency: BTreeMap<u64, K>,
+    capacity: Option<usize>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Cache<K, V> {
+    fn new(capacity: Option<usize>) -> Self {
+        Cache {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            capacity,
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    // counts a hit or a miss
+    fn lookup(&mut self, k: &K) -> Option<V> {
+        self.tick += 1;
+        match self.entries.get_mut(k) {
+            Some((v, used)) => {
+                let k = self.recency.remove(used).expect("every entry has a tick");
+                self.recency.insert(self.tick, k);
+                *used = self.tick;
+                self.hits += 1;
+                Some(v.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn insert(&mut self, k: K, v: V) {
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                return;
+            }
+            if self.entries.len() >= capacity && !self.entries.contains_key(&k) {
+                self.evict();
+            }
+        }
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(k.clone(), (v, self.tick)) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(self.tick, k);
+    }
+
+    // O(log n): the oldest tick is the first in `recency`
+    fn evict(&mut self) {
+        if let Some((_, k)) = self.recency.pop_first() {
+            self.entries.remove(&k);
+        }
+    }
+}
+
+pub struct Memo<F, K, V> {
+    f: F,
+    cache: Cache<K, V>,
+}
+
+impl<F, K, V> Memo<F, K, V>
+where
+    F: Fn(K) -> V,
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(f: F) -> Self {
+        Memo {
+            f,
+            cache: Cache::new(None),
+        }
+    }
+
+    // keep at most `capacity` results
+    pub fn with_capacity(f: F, capacity: usize) -> Self {
+        Memo {
+            f,
+            cache: Cache::new(Some(capacity)),
+        }
+    }
+
+    pub fn get(&mut self, k: K) -> V {
+        if let Some(v) = self.cache.lookup(&k) {
+            return v;
+        }
+        let v = (self.f)(k.clone());
+        self.cache.insert(k, v.clone());
+        v
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.cache.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.cache.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.entries.is_empty()
+    }
+}
+
+// `f` gets the memoized version of itself as its first argument to recurse through
+pub struct RecursiveMemo<F, K, V> {
+    f: F,
+    cache: Cache<K, V>,
+}
+
+impl<F, K, V> RecursiveMemo<F, K, V>
+where
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(f: F) -> Self {
+        RecursiveMemo {
+            f,
+            cache: Cache::new(None),
+        }
+    }
+
+    pub fn with_capacity(f: F, capacity: usize) -> Self {
+        RecursiveMemo {
+            f,
+            cache: Cache::new(Some(capacity)),
+        }
+    }
+
+    pub fn get(&mut self, k: K) -> V {
+        fn recurse<F, K, V>(f: &F, cache: &mut Cache<K, V>, k: K) -> V
+        where
+            F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+            K: Eq + Hash + Clone,
+            V: Clone,
+        {
+            if let Some(v) = cache.lookup(&k) {
+                return v;
+            }
+            let v = f(&mut |k| recurse(f, cache, k), k.clone());
+            cache.insert(k, v.clone());
+            v
+        }
+        recurse(&self.f, &mut self.cache, k)
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.cache.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.cache.misses
+    }
+}
+
+// `get` takes `&self`, so one `SyncMemo` can be shared by reference or in an `Arc`.
+// the lock is not held while `f` runs: two threads missing the same key at once both compute
+// it, which for a pure `f` only costs time
+pub struct SyncMemo<F, K, V> {
+    f: F,
+    cache: Mutex<Cache<K, V>>,
+}
+
+impl<F, K, V> SyncMemo<F, K, V>
+where
+    F: Fn(K) -> V,
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new(f: F) -> Self {
+        SyncMemo {
+            f,
+            cache: Mutex::new(Cache::new(None)),
+        }
+    }
+
+    pub fn with_capacity(f: F, capacity: usize) -> Self {
+        SyncMemo {
+            f,
+            cache: Mutex::new(Cache::new(Some(capacity))),
+        }
+    }
+
+    pub fn get(&self, k: K) -> V {
+        if let Some(v) = self.cache.lock().unwrap().lookup(&k) {
+            return v;
+        }
+        let v = (self.f)(k.clone());
+        self.cache.lock().unwrap().insert(k, v.clone());
+        v
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.cache.lock().unwrap().hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.cache.lock().unwrap().misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fibonacci::fibonacci;
+    use std::cell::Cell;
+    use std::thread;
+
+    #[test]
+    fn test_caches_results() {
+        let calls = Cell::new(0);
+        let mut square = Memo::new(|x: u64| {
+            calls.set(calls.get() + 1);
+            x * x
+        });
+        assert_eq!(square.get(4), 16);
+        assert_eq!(square.get(4), 16);
+        assert_eq!(square.get(5), 25);
+        assert_eq!(calls.get(), 2);
+        assert_eq!((square.hits(), square.misses()), (1, 2));
+        assert_eq!(square.len(), 2);
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut memo = Memo::with_capacity(|s: String| s.len(), 2);
+        memo.get("a".to_owned());
+        memo.get("bb".to_owned());
+        // touching "a" makes "bb" the least recently used
+        memo.get("a".to_owned());
+        memo.get("ccc".to_owned());
+        assert_eq!(memo.len(), 2);
+        let misses = memo.misses();
+        memo.get("a".to_owned());
+        assert_eq!(memo.misses(), misses);
+        memo.get("bb".to_owned());
+        assert_eq!(memo.misses(), misses + 1);
+
+        // a long run past the capacity keeps exactly the most recent keys
+        let mut recent = Memo::with_capacity(|x: u32| x * 2, 100);
+        for x in 0..100_000 {
+            recent.get(x);
+        }
+        assert_eq!(recent.len(), 100);
+        let misses = recent.misses();
+        for x in 99_900..100_000 {
+            recent.get(x);
+        }
+        assert_eq!(recent.misses(), misses);
+
+        let mut nothing = Memo::with_capacity(|x: i32| x, 0);
+        nothing.get(1);
+        nothing.get(1);
+        assert!(nothing.is_empty());
+        assert_eq!(nothing.misses(), 2);
+    }
+
+    #[test]
+    fn test_recursive_fibonacci() {
+        let mut fib = RecursiveMemo::new(
+            |fib: &mut dyn FnMut(u64) -> u64, n: u64| {
+                if n < 2 {
+                    n
+                } else {
+                    fib(n - 1) + fib(n - 2)
+                }
+            },
+        );
+        assert_eq!(fib.get(90), 2_880_067_194_370_816_120);
+        // every n from 0 to 90 is computed exactly once
+        assert_eq!(fib.misses(), 91);
+
+        // the iterator from the traits crate: 1, 2, 3, 5, ... is fib(2), fib(3), ...
+        for (i, expected) in fibonacci().take(40).enumerate() {
+            assert_eq!(fib.get(i as u64 + 2), u64::from(expected));
+        }
+        assert_eq!(fib.misses(), 91);
+    }
+
+    // the iterator walks from the start for every index, the memo computes each index once
+    #[test]
+    fn test_random_access_beats_the_iterator() {
+        let mut fib = RecursiveMemo::new(
+            |fib: &mut dyn FnMut(u32) -> u32, n: u32| {
+                if n < 2 {
+                    n
+                } else {
+                    fib(n - 1) + fib(n - 2)
+                }
+            },
+        );
+        // 2..=46 in a scrambled order, fib(47) is the last the u32 iterator can get past
+        let indices: Vec<u32> = (0..1_000).map(|i| 2 + i * 7919 % 45).collect();
+        let mut steps = 0;
+        for &n in &indices {
+            let nth = fibonacci()
+                .inspect(|_| steps += 1)
+                .nth(n as usize - 2)
+                .unwrap();
+            assert_eq!(fib.get(n), nth);
+        }
+        // 0..=46 miss once each, and each of the 45 from 2 up looks up its two predecessors
+        assert_eq!(fib.misses(), 47);
+        assert_eq!(fib.hits() + fib.misses(), 1_000 + 2 * 45);
+        assert!(steps > 20 * (fib.hits() + fib.misses()));
+    }
+
+    #[test]
+    fn test_recursive_with_capacity_still_correct() {
+        let mut fib = RecursiveMemo::with_capacity(
+            |fib: &mut dyn FnMut(u32) -> u128, n: u32| {
+                if n < 2 {
+                    n.into()
+                } else {
+                    fib(n - 1) + fib(n - 2)
+                }
+            },
+            3,
+        );
+        assert_eq!(fib.get(150), 9_969_216_677_189_303_386_214_405_760_200);
+        assert!(fib.hits() > 0);
+    }
+
+    #[test]
+    fn test_sync_memo_across_threads() {
+        let memo = SyncMemo::new(|n: u64| (1..=n).product::<u64>());
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for n in 0..20 {
+                        assert_eq!(memo.get(n), (1..=n).product::<u64>());
+                    }
+                });
+            }
+        });
+        assert_eq!(memo.hits() + memo.misses(), 80);
+        // each key misses at least once and at most once per thread
+        assert!(memo.misses() >= 20 && memo.misses() <= 80);
+        assert!(memo.get(10) == 3_628_800 && memo.hits() > 0);
+    }
+}
@@ -0,0 +1,289 @@
+// 2D affine transforms for `Point` and `Rectangle`.
+// a `Transform` maps (x, y) to (a*x + c*y + e, b*x + d*y + f). translating, scaling, rotating
+// and shearing are all of that form, so any sequence of them composes into one `Transform`.
+// a rectangle that is rotated or sheared is no longer axis aligned, so transforming a
+// `Rectangle` gives a `Quad`: its four corners in order, measured with the shoelace formula.
+
+use super::{Point, Rectangle};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    a: f64,
+    b: f64,
+    c: f64,
+    d: f64,
+    e: f64,
+    f: f64,
+}
+
+impl Transform {
+    pub fn identity() -> Transform {
+        Transform::linear(1.0, 0.0, 0.0, 1.0)
+    }
+
+    // the transform without translation, (x, y) to (a*x + c*y, b*x + d*y)
+    fn linear(a: f64, b: f64, c: f64, d: f64) -> Transform {
+        Transform {
+            a,
+            b,
+            c,
+            d,
+            e: 0.0,
+            f: 0.0,
+        }
+    }
+
+    pub fn translate(x: f64, y: f64) -> Transform {
+        Transform {
+            e: x,
+            f: y,
+            ..Transform::identity()
+        }
+    }
+
+    pub fn scale(x: f64, y: f64) -> Transform {
+        Transform::linear(x, 0.0, 0.0, y)
+    }
+
+    // counterclockwise around the origin
+    pub fn rotate(radians: f64) -> Transform {
+        let (sin, cos) = radians.sin_cos();
+        Transform::linear(cos, sin, -sin, cos)
+    }
+
+    // (x, y) to (x + kx*y, ky*x + y)
+    pub fn shear(kx: f64, ky: f64) -> Transform {
+        Transform::linear(1.0, ky, kx, 1.0)
+    }
+
+    // `self` first, then `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    // how much areas are scaled, negative when the transform mirrors
+    pub fn determinant(&self) -> f64 {
+        self.a * self.d - self.b * self.c
+    }
+
+    // `None` when the transform flattens the plane, like `scale(0.0, 1.0)`.
+    // `a*d - b*c` can only be trusted up to rounding in its two products, so the determinant
+    // is compared to their size, not to a fixed threshold: `scale(1e-8, 1e-8)` is invertible
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.determinant();
+        let rounding = 4.0 * f64::EPSILON * ((self.a * self.d).abs() + (self.b * self.c).abs());
+        if !det.is_finite() || det.abs() <= rounding {
+            return None;
+        }
+        let linear = Transform::linear(self.d / det, -self.b / det, -self.c / det, self.a / det);
+        // undo the translation first, then the linear part
+        Some(Transform::translate(-self.e, -self.f).then(&linear))
+    }
+
+    pub fn apply(&self, p: &Point) -> Point {
+        Point::new(
+            self.a * p.x + self.c * p.y + self.e,
+            self.b * p.x + self.d * p.y + self.f,
+        )
+    }
+}
+
+// four corners in order around the edge
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quad {
+    pub corners: [Point; 4],
+}
+
+impl Quad {
+    pub fn area(&self) -> f64 {
+        let twice: f64 = self
+            .edges()
+            .iter()
+            .map(|(p, q)| p.x * q.y - q.x * p.y)
+            .sum();
+        (twice / 2.0).abs()
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .iter()
+            .map(|(p, q)| (q.x - p.x).hypot(q.y - p.y))
+            .sum()
+    }
+
+    fn edges(&self) -> [(Point, Point); 4] {
+        let c = self.corners;
+        [(c[0], c[1]), (c[1], c[2]), (c[2], c[3]), (c[3], c[0])]
+    }
+
+    pub fn transform(&self, t: &Transform) -> Quad {
+        let c = &self.corners;
+        Quad {
+            corners: [
+                t.apply(&c[0]),
+                t.apply(&c[1]),
+                t.apply(&c[2]),
+                t.apply(&c[3]),
+            ],
+        }
+    }
+
+    // the smallest axis aligned rectangle around the corners
+    pub fn bounding_box(&self) -> Rectangle {
+        let xs = self.corners.iter().map(|p| p.x);
+        let ys = self.corners.iter().map(|p| p.y);
+        Rectangle {
+            p1: Point::new(
+                xs.clone().fold(f64::INFINITY, f64::min),
+                ys.clone().fold(f64::INFINITY, f64::min),
+            ),
+            p2: Point::new(
+                xs.fold(f64::NEG_INFINITY, f64::max),
+                ys.fold(f64::NEG_INFINITY, f64::max),
+            ),
+        }
+    }
+}
+
+impl Rectangle {
+    // starting at `p1`, going to `p1.x, p2.y` last
+    pub fn corners(&self) -> [Point; 4] {
+        let (p1, p2) = (self.p1, self.p2);
+        [p1, Point::new(p2.x, p1.y), p2, Point::new(p1.x, p2.y)]
+    }
+
+    pub fn to_quad(&self) -> Quad {
+        Quad {
+            corners: self.corners(),
+        }
+    }
+
+    pub fn transform(&self, t: &Transform) -> Quad {
+        self.to_quad().transform(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn assert_close(p: Point, q: Point) {
+        assert!(
+            (p.x - q.x).abs() < 1e-9 && (p.y - q.y).abs() < 1e-9,
+            "{:?} != {:?}",
+            p,
+            q
+        );
+    }
+
+    fn rectangle() -> Rectangle {
+        Rectangle {
+            p1: Point::new(1.0, 1.0),
+            p2: Point::new(4.0, 3.0),
+        }
+    }
+
+    #[test]
+    fn test_translate_matches_transform() {
+        let mut moved = rectangle();
+        moved.translate(2.5, -1.0);
+        let transformed = rectangle().transform(&Transform::translate(2.5, -1.0));
+        assert_eq!(transformed.corners, moved.corners());
+        assert_eq!(transformed.area(), moved.area());
+        assert_eq!(transformed.perimeter(), moved.perimeter());
+    }
+
+    #[test]
+    fn test_basic_transforms() {
+        let p = Point::new(2.0, 1.0);
+        assert_close(Transform::scale(3.0, -1.0).apply(&p), Point::new(6.0, -1.0));
+        assert_close(
+            Transform::rotate(FRAC_PI_2).apply(&p),
+            Point::new(-1.0, 2.0),
+        );
+        assert_close(Transform::shear(1.0, 0.0).apply(&p), Point::new(3.0, 1.0));
+        assert_eq!(Transform::identity().apply(&p), p);
+    }
+
+    #[test]
+    fn test_composition_order() {
+        let p = Point::new(1.0, 0.0);
+        let shift_then_turn = Transform::translate(1.0, 0.0).then(&Transform::rotate(FRAC_PI_2));
+        let turn_then_shift = Transform::rotate(FRAC_PI_2).then(&Transform::translate(1.0, 0.0));
+        assert_close(shift_then_turn.apply(&p), Point::new(0.0, 2.0));
+        assert_close(turn_then_shift.apply(&p), Point::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let t = Transform::scale(2.0, 0.5)
+            .then(&Transform::shear(0.3, 0.0))
+            .then(&Transform::rotate(1.0))
+            .then(&Transform::translate(-4.0, 7.0));
+        let inverse = t.inverse().unwrap();
+        for p in rectangle().corners().iter() {
+            assert_close(inverse.apply(&t.apply(p)), *p);
+            assert_close(t.then(&inverse).apply(p), *p);
+        }
+        assert_eq!(Transform::scale(0.0, 1.0).inverse(), None);
+        assert_eq!(Transform::scale(f64::NAN, 1.0).inverse(), None);
+        // the shear that makes both axes the same line
+        assert_eq!(Transform::shear(1.0, 1.0).inverse(), None);
+        assert_eq!(
+            Transform::rotate(1.0)
+                .then(&Transform::scale(3.0, 0.0))
+                .inverse(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_inverse_of_small_scales() {
+        let t = Transform::scale(1e-8, 1e-8).then(&Transform::rotate(0.5));
+        let inverse = t.inverse().unwrap();
+        for p in rectangle().corners().iter() {
+            assert_close(inverse.apply(&t.apply(p)), *p);
+        }
+        assert_close(
+            Transform::scale(1e-8, 1e-8)
+                .inverse()
+                .unwrap()
+                .apply(&Point::new(1e-8, -2e-8)),
+            Point::new(1.0, -2.0),
+        );
+    }
+
+    #[test]
+    fn test_area_and_perimeter_after_transforms() {
+        let r = rectangle();
+        let rotated = r.transform(&Transform::rotate(FRAC_PI_4));
+        assert!((rotated.area() - r.area()).abs() < 1e-9);
+        assert!((rotated.perimeter() - r.perimeter()).abs() < 1e-9);
+
+        let scaled = r.transform(&Transform::scale(2.0, 3.0));
+        assert!((scaled.area() - 6.0 * r.area()).abs() < 1e-9);
+        assert!((scaled.perimeter() - 2.0 * (6.0 + 6.0)).abs() < 1e-9);
+
+        // shearing keeps the area but stretches two of the sides
+        let sheared = r.transform(&Transform::shear(1.0, 0.0));
+        assert!((sheared.area() - r.area()).abs() < 1e-9);
+        assert!((sheared.perimeter() - (6.0 + 2.0 * 8f64.sqrt())).abs() < 1e-9);
+
+        let square = Rectangle {
+            p1: Point::origin(),
+            p2: Point::new(2.0, 2.0),
+        };
+        let diamond = square
+            .transform(&Transform::rotate(FRAC_PI_4))
+            .bounding_box();
+        assert!((diamond.area() - 8.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,302 @@
+// `AContains` taken further: `Collection` names its item type once, as an associated type, and
+// works for any number of items instead of a pair of i32s. the std collections, slices, arrays
+// and both pair containers implement it, so `difference`, `span` and `all_within` below take
+// any of them without spelling out more type parameters, as `difference` in main.rs has to.
+use std::collections::{HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::ops::{RangeBounds, Sub};
+
+use super::{AContainer, Container};
+
+pub trait Collection {
+    type Item;
+
+    fn len(&self) -> usize;
+    fn first(&self) -> Option<&Self::Item>;
+    fn last(&self) -> Option<&Self::Item>;
+    fn contains(&self, item: &Self::Item) -> bool;
+    // every item, from first to last
+    fn iter(&self) -> Box<dyn Iterator<Item = &Self::Item> + '_>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: PartialEq> Collection for [T] {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn first(&self) -> Option<&T> {
+        <[T]>::first(self)
+    }
+
+    fn last(&self) -> Option<&T> {
+        <[T]>::last(self)
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        <[T]>::contains(self, item)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(<[T]>::iter(self))
+    }
+}
+
+impl<T: PartialEq, const N: usize> Collection for [T; N] {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn first(&self) -> Option<&T> {
+        Collection::first(&self[..])
+    }
+
+    fn last(&self) -> Option<&T> {
+        Collection::last(&self[..])
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        Collection::contains(&self[..], item)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Collection::iter(&self[..])
+    }
+}
+
+impl<T: PartialEq> Collection for Vec<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn first(&self) -> Option<&T> {
+        Collection::first(self.as_slice())
+    }
+
+    fn last(&self) -> Option<&T> {
+        Collection::last(self.as_slice())
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        Collection::contains(self.as_slice(), item)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Collection::iter(self.as_slice())
+    }
+}
+
+impl<T: PartialEq> Collection for VecDeque<T> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn first(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn last(&self) -> Option<&T> {
+        self.back()
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        VecDeque::contains(self, item)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(VecDeque::iter(self))
+    }
+}
+
+// a set keeps no order of its own, so it goes from its smallest item to its largest
+impl<T: Eq + Hash + Ord, S: BuildHasher> Collection for HashSet<T, S> {
+    type Item = T;
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn first(&self) -> Option<&T> {
+        HashSet::iter(self).min()
+    }
+
+    fn last(&self) -> Option<&T> {
+        HashSet::iter(self).max()
+    }
+
+    fn contains(&self, item: &T) -> bool {
+        HashSet::contains(self, item)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &T> + '_> {
+        let mut items: Vec<&T> = HashSet::iter(self).collect();
+        items.sort();
+        Box::new(items.into_iter())
+    }
+}
+
+// the pairs always have both their numbers
+impl Collection for Container {
+    type Item = i32;
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn first(&self) -> Option<&i32> {
+        Some(&self.0)
+    }
+
+    fn last(&self) -> Option<&i32> {
+        Some(&self.1)
+    }
+
+    fn contains(&self, item: &i32) -> bool {
+        &self.0 == item || &self.1 == item
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &i32> + '_> {
+        Box::new(std::iter::once(&self.0).chain(std::iter::once(&self.1)))
+    }
+}
+
+impl Collection for AContainer {
+    type Item = i32;
+
+    fn len(&self) -> usize {
+        2
+    }
+
+    fn first(&self) -> Option<&i32> {
+        Some(&self.0)
+    }
+
+    fn last(&self) -> Option<&i32> {
+        Some(&self.1)
+    }
+
+    fn contains(&self, item: &i32) -> bool {
+        &self.0 == item || &self.1 == item
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = &i32> + '_> {
+        Box::new(std::iter::once(&self.0).chain(std::iter::once(&self.1)))
+    }
+}
+
+// last minus first, like `a_difference`; `None` when there are no items
+pub fn difference<C>(collection: &C) -> Option<C::Item>
+where
+    C: Collection + ?Sized,
+    C::Item: Copy + Sub<Output = C::Item>,
+{
+    Some(*collection.last()? - *collection.first()?)
+}
+
+// largest minus smallest, wherever they are
+pub fn span<C>(collection: &C) -> Option<C::Item>
+where
+    C: Collection + ?Sized,
+    C::Item: Copy + Ord + Sub<Output = C::Item>,
+{
+    let min = collection.iter().min()?;
+    let max = collection.iter().max()?;
+    Some(*max - *min)
+}
+
+// whether every item lies in `range`, which an empty collection always does
+pub fn all_within<C, R>(collection: &C, range: R) -> bool
+where
+    C: Collection + ?Sized,
+    C::Item: PartialOrd,
+    R: RangeBounds<C::Item>,
+{
+    collection.iter().all(|item| range.contains(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_collection_agrees() {
+        let items = [3, 10, -2, 7];
+        let vec = items.to_vec();
+        let deque: VecDeque<i32> = items.iter().copied().collect();
+        let slice: &[i32] = &items;
+
+        assert_eq!(Collection::len(&items), 4);
+        assert_eq!(Collection::len(&vec), 4);
+        assert_eq!(Collection::len(&deque), 4);
+        assert_eq!(Collection::len(slice), 4);
+
+        assert_eq!(difference(&items), Some(4));
+        assert_eq!(difference(&vec), Some(4));
+        assert_eq!(difference(&deque), Some(4));
+        assert_eq!(difference(slice), Some(4));
+
+        assert_eq!(span(&items), Some(12));
+        assert_eq!(span(&deque), Some(12));
+        assert!(Collection::contains(&deque, &-2));
+        assert!(!Collection::contains(slice, &4));
+        assert_eq!(
+            Collection::iter(&deque).collect::<Vec<_>>(),
+            Collection::iter(&vec).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_hash_set_goes_in_order() {
+        let set: HashSet<i32> = [40, -5, 12, 7].iter().copied().collect();
+        assert_eq!(Collection::first(&set), Some(&-5));
+        assert_eq!(Collection::last(&set), Some(&40));
+        assert_eq!(
+            Collection::iter(&set).copied().collect::<Vec<_>>(),
+            vec![-5, 7, 12, 40]
+        );
+        assert_eq!(difference(&set), span(&set));
+        assert!(Collection::contains(&set, &12));
+    }
+
+    #[test]
+    fn test_pairs() {
+        let container = Container(3, 10);
+        let a_container = AContainer(10, 3);
+        assert_eq!(difference(&container), Some(7));
+        assert_eq!(difference(&a_container), Some(-7));
+        assert_eq!(span(&a_container), Some(7));
+        assert!(Collection::contains(&container, &10));
+        assert!(!Collection::contains(&a_container, &4));
+        assert!(all_within(&container, 3..=10));
+        assert!(!all_within(&container, 3..10));
+    }
+
+    #[test]
+    fn test_empty() {
+        let empty: Vec<i32> = Vec::new();
+        assert!(Collection::is_empty(&empty));
+        assert_eq!(difference(&empty), None);
+        assert_eq!(span(&empty), None);
+        assert!(all_within(&empty, 0..0));
+        assert!(Collection::is_empty(&[0u8; 0]));
+    }
+
+    #[test]
+    fn test_all_within() {
+        assert!(all_within(&vec![0.5, 0.25, 1.0], 0.0..=1.0));
+        assert!(!all_within(&vec![0.5, 1.5], ..1.0));
+        let words = vec!["apple", "pear"];
+        assert!(all_within(&words, "a".."q"));
+    }
+}
@@ -0,0 +1,446 @@
+// civil dates in the proleptic Gregorian calendar, and durations counted in days.
+// a `Date` is a year, month and day that exist: February 29 only in leap years, which are the
+// years divisible by 4 except the centuries not divisible by 400 (2000 was one, 1900 and 2100
+// are not). dates convert to and from a day number, days since 1970-01-01, which makes the
+// distance between two dates exact, leap days included.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+use super::Years;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateError {
+    // the day does not exist, like 2023-02-29 or month 13
+    OutOfRange { year: i32, month: u32, day: u32 },
+    // not YYYY-MM-DD
+    Format(String),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateError::OutOfRange { year, month, day } => write!(
+                f,
+                "{:04}-{:02}-{:02} is not a day of the calendar",
+                year, month, day
+            ),
+            DateError::Format(s) => write!(f, "{:?} is not a YYYY-MM-DD date", s),
+        }
+    }
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Date, DateError> {
+        if (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day) {
+            Ok(Date {
+                year,
+                month: month as u8,
+                day: day as u8,
+            })
+        } else {
+            Err(DateError::OutOfRange { year, month, day })
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month.into()
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day.into()
+    }
+
+    // days since 1970-01-01, negative before it.
+    // years are shifted to start in March, so the leap day is the last day of its year, and
+    // counted in 400 year eras of 146097 days
+    pub fn to_days(self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - (month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        // 719468 days from 0000-03-01 to 1970-01-01
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    // `None` when the year does not fit an `i32`
+    pub fn from_days(days: i64) -> Option<Date> {
+        let days = days.checked_add(719_468)?;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = (shifted_month + 2) % 12 + 1;
+        let year = era
+            .checked_mul(400)?
+            .checked_add(year_of_era + (month <= 2) as i64)?;
+        Some(Date {
+            year: i32::try_from(year).ok()?,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        match (self.to_days() + 3).rem_euclid(7) {
+            0 => Weekday::Monday,
+            1 => Weekday::Tuesday,
+            2 => Weekday::Wednesday,
+            3 => Weekday::Thursday,
+            4 => Weekday::Friday,
+            5 => Weekday::Saturday,
+            _ => Weekday::Sunday,
+        }
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Date> {
+        Date::from_days(self.to_days().checked_add(duration.days)?)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Date> {
+        Date::from_days(self.to_days().checked_sub(duration.days)?)
+    }
+}
+
+// ISO 8601, YYYY-MM-DD, with a sign for years before 0 or after 9999
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.year < 0 {
+            write!(f, "-")?;
+        } else if self.year > 9999 {
+            write!(f, "+")?;
+        }
+        write!(
+            f,
+            "{:04}-{:02}-{:02}",
+            self.year.unsigned_abs(),
+            self.month,
+            self.day
+        )
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Date, DateError> {
+        let format = || DateError::Format(s.to_owned());
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'-') => (-1, &s[1..]),
+            Some(b'+') => (1, &s[1..]),
+            _ => (1, s),
+        };
+        let mut parts = rest.split('-');
+        let (year, month, day) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(y), Some(m), Some(d), None) if y.len() >= 4 && m.len() == 2 && d.len() == 2 => {
+                (y, m, d)
+            }
+            _ => return Err(format()),
+        };
+        let digits = |part: &str| -> Result<u32, DateError> {
+            if part.bytes().all(|b| b.is_ascii_digit()) {
+                part.parse().map_err(|_| format())
+            } else {
+                Err(format())
+            }
+        };
+        let year = i32::try_from(digits(year)?).map_err(|_| format())? * sign;
+        Date::new(year, digits(month)?, digits(day)?)
+    }
+}
+
+// a number of days, negative for going back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Duration {
+    days: i64,
+}
+
+impl Duration {
+    pub fn days(days: i64) -> Duration {
+        Duration { days }
+    }
+
+    pub fn weeks(weeks: i64) -> Option<Duration> {
+        weeks.checked_mul(7).map(Duration::days)
+    }
+
+    pub fn num_days(&self) -> i64 {
+        self.days
+    }
+
+    pub fn checked_add(&self, other: Duration) -> Option<Duration> {
+        self.days.checked_add(other.days).map(Duration::days)
+    }
+
+    pub fn checked_sub(&self, other: Duration) -> Option<Duration> {
+        self.days.checked_sub(other.days).map(Duration::days)
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = if self.days.abs() == 1 { "day" } else { "days" };
+        write!(f, "{} {}", self.days, unit)
+    }
+}
+
+// the operators panic where the checked methods give `None`, like the integer ones in debug builds
+impl Add<Duration> for Date {
+    type Output = Date;
+
+    fn add(self, rhs: Duration) -> Date {
+        self.checked_add(rhs).expect("date out of range")
+    }
+}
+
+impl Sub<Duration> for Date {
+    type Output = Date;
+
+    fn sub(self, rhs: Duration) -> Date {
+        self.checked_sub(rhs).expect("date out of range")
+    }
+}
+
+// the days from `rhs` to `self`, which can never overflow: the day numbers fit in 40 bits
+impl Sub for Date {
+    type Output = Duration;
+
+    fn sub(self, rhs: Date) -> Duration {
+        Duration::days(self.to_days() - rhs.to_days())
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Duration) -> Duration {
+        self.checked_add(rhs).expect("duration overflow")
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Duration) -> Duration {
+        self.checked_sub(rhs).expect("duration overflow")
+    }
+}
+
+// whole years from `birth` to `today`, `None` if `today` comes first.
+// a birthday on February 29 comes round on March 1 in the years without one
+pub fn age_on(birth: Date, today: Date) -> Option<Years> {
+    if today < birth {
+        return None;
+    }
+    let had_birthday = (today.month, today.day) >= (birth.month, birth.day);
+    let years = i64::from(today.year) - i64::from(birth.year) - !had_birthday as i64;
+    Some(Years(years))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    fn age(birth: &str, today: &str) -> i64 {
+        age_on(date(birth), date(today)).unwrap().0
+    }
+
+    #[test]
+    fn test_leap_years() {
+        assert!(is_leap_year(2024));
+        assert!(!is_leap_year(2023));
+        assert!(!is_leap_year(1900));
+        assert!(!is_leap_year(2100));
+        assert!(is_leap_year(2000));
+        assert!(is_leap_year(1600));
+        assert!(is_leap_year(-4));
+        assert_eq!(days_in_month(1900, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert!(Date::new(2000, 2, 29).is_ok());
+        assert_eq!(
+            Date::new(1900, 2, 29),
+            Err(DateError::OutOfRange {
+                year: 1900,
+                month: 2,
+                day: 29
+            })
+        );
+        assert!(Date::new(2023, 13, 1).is_err());
+        assert!(Date::new(2023, 4, 31).is_err());
+        assert!(Date::new(2023, 1, 0).is_err());
+    }
+
+    #[test]
+    fn test_day_numbers() {
+        assert_eq!(date("1970-01-01").to_days(), 0);
+        assert_eq!(date("1969-12-31").to_days(), -1);
+        assert_eq!(date("2000-03-01").to_days(), 11_017);
+        // 400 years always have the same number of days
+        assert_eq!(
+            date("2400-01-01") - date("2000-01-01"),
+            Duration::days(146_097)
+        );
+        // every day from 1599 to 2401 in order, one apart
+        let mut day = date("1599-01-01");
+        let mut n = day.to_days();
+        while day < date("2401-12-31") {
+            let next = day + Duration::days(1);
+            assert_eq!(Date::from_days(n), Some(day));
+            assert!(next > day);
+            assert_eq!(next.to_days(), n + 1);
+            day = next;
+            n += 1;
+        }
+        assert_eq!(Date::from_days(i64::MAX), None);
+        let first = Date::new(i32::MIN, 1, 1).unwrap();
+        assert_eq!(Date::from_days(first.to_days()), Some(first));
+        assert_eq!(first.checked_sub(Duration::days(1)), None);
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(date("1970-01-01").weekday(), Weekday::Thursday);
+        assert_eq!(date("2000-01-01").weekday(), Weekday::Saturday);
+        assert_eq!(date("1900-01-01").weekday(), Weekday::Monday);
+        assert_eq!(date("2024-02-29").weekday(), Weekday::Thursday);
+        assert_eq!(date("1969-12-28").weekday(), Weekday::Sunday);
+        assert_eq!(date("1582-10-15").weekday().to_string(), "Friday");
+    }
+
+    #[test]
+    fn test_iso_8601() {
+        let d = date("2024-02-29");
+        assert_eq!((d.year(), d.month(), d.day()), (2024, 2, 29));
+        assert_eq!(d.to_string(), "2024-02-29");
+        assert_eq!(date("0009-01-05").to_string(), "0009-01-05");
+        assert_eq!(date("-0044-03-15").to_string(), "-0044-03-15");
+        assert_eq!(date("+12345-06-07").to_string(), "+12345-06-07");
+        for bad in [
+            "2024-2-29",
+            "24-02-29",
+            "2024/02/29",
+            "2024-02-29-01",
+            "2024-0x-01",
+            "2024-+1-01",
+            "",
+        ] {
+            assert_eq!(bad.parse::<Date>(), Err(DateError::Format(bad.to_owned())));
+        }
+        assert!(matches!(
+            "2023-02-29".parse::<Date>(),
+            Err(DateError::OutOfRange { .. })
+        ));
+        assert_eq!(
+            "2023-02-29".parse::<Date>().unwrap_err().to_string(),
+            "2023-02-29 is not a day of the calendar"
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        let d = date("2023-12-25");
+        assert_eq!(d + Duration::days(7), date("2024-01-01"));
+        assert_eq!(d - Duration::weeks(52).unwrap(), date("2022-12-26"));
+        assert_eq!(date("2024-03-01") - date("2024-02-28"), Duration::days(2));
+        assert_eq!(date("2023-03-01") - date("2023-02-28"), Duration::days(1));
+        assert_eq!(
+            date("2023-01-01") - date("2024-01-01"),
+            Duration::days(-365)
+        );
+        assert_eq!(Duration::days(1).to_string(), "1 day");
+        assert_eq!(Duration::days(-3).to_string(), "-3 days");
+        assert_eq!(
+            Duration::days(i64::MAX).checked_add(Duration::days(1)),
+            None
+        );
+        assert_eq!(Duration::weeks(i64::MAX / 2), None);
+        assert_eq!(d.checked_add(Duration::days(i64::MAX)), None);
+        assert_eq!(
+            Duration::days(5) - Duration::days(7) + Duration::default(),
+            Duration::days(-2)
+        );
+    }
+
+    #[test]
+    fn test_age_on() {
+        assert_eq!(age("2000-06-15", "2018-06-14"), 17);
+        assert_eq!(age("2000-06-15", "2018-06-15"), 18);
+        assert_eq!(age("2000-06-15", "2000-06-15"), 0);
+        assert!(age_on(date("2000-06-15"), date("2000-06-14")).is_none());
+        // 365 days a year gets this one wrong: the leap days make up for the missing one
+        let born = date("2000-06-15");
+        let old_days = (date("2018-06-14") - born).num_days();
+        assert_eq!(old_days / 365, 18);
+        assert!(!crate::old_enough(
+            &age_on(born, date("2018-06-14")).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_february_29_birthdays() {
+        assert_eq!(age("2004-02-29", "2022-02-28"), 17);
+        assert_eq!(age("2004-02-29", "2022-03-01"), 18);
+        assert_eq!(age("2004-02-29", "2024-02-28"), 19);
+        assert_eq!(age("2004-02-29", "2024-02-29"), 20);
+        // 2100 is a century without a leap day
+        assert_eq!(age("2000-02-29", "2100-02-28"), 99);
+        assert_eq!(age("2000-02-29", "2100-03-01"), 100);
+        // 2000 had one, being divisible by 400
+        assert_eq!(age("1996-02-29", "2000-02-29"), 4);
+        assert_eq!(age("1896-02-29", "1900-02-28"), 3);
+        assert_eq!(age("1896-02-29", "1900-03-01"), 4);
+    }
+}
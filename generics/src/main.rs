@@ -1,9 +1,25 @@
+use std::collections::HashSet;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 
 use std::marker::PhantomData;
 use std::ops::Add;
 
+// conversion's circle, shapes.rs makes it a `Shape`
+#[path = "../../conversion/src/circle.rs"]
+mod circle;
+mod collection;
+mod date;
+mod shapes;
+mod typestate;
+mod units;
+
+use circle::Circle;
+use date::{Date, Duration};
+use shapes::{Point, Polygon, RegularPolygon, Scene};
+use typestate::{Closed, Connection, Open, Settings, Unconfigured};
+use units::{Foot, Hour, Kg, Km, Metre, Pound, Prod, Quantity, Quot, Second};
+
 // a generic function takes an argument T for any type
 fn foo<T>(arg: T) {}
 
@@ -90,18 +106,46 @@ fn main() {
         length: 3.0,
         height: 4.0,
     };
-    let _triangle = Triangle {
-        length: 3.0,
-        height: 4.0,
-    };
+    let _triangle = Triangle::new(
+        Point::new(0.0, 0.0),
+        Point::new(3.0, 0.0),
+        Point::new(0.0, 4.0),
+    );
 
     print_debug(&rectangle);
     print_debug_where_clauses(&rectangle);
     println!("Area: {}", area(&rectangle));
 
     // print_debug(&_triangle);
-    // the trait `HasArea` is not implemented for `Triangle`
-    // println!("Area: {}", area(&_triangle));
+    // the trait `Debug` is not implemented for `Triangle`, `HasArea` is now
+    println!("Area: {}", area(&_triangle));
+
+    // every `Shape` is `HasArea` too, and they all fit in one scene
+    let hexagon = RegularPolygon::new(Point::new(8.0, 0.0), 6, 2.0).unwrap();
+    let arrow = Polygon::new(vec![
+        Point::new(0.0, 0.0),
+        Point::new(4.0, 2.0),
+        Point::new(0.0, 4.0),
+        Point::new(1.0, 2.0),
+    ])
+    .unwrap();
+    println!("Area: {}", area(&arrow));
+    let scene = Scene::new()
+        .add(rectangle)
+        .add(_triangle)
+        .add(Circle { radius: 2 })
+        .add(hexagon)
+        .add(arrow);
+    println!("{}", scene);
+    if let Some(bounds) = scene.bounding_box() {
+        println!(
+            "all {} shapes fit in {:.2} by {:.2}, starting at {}",
+            scene.shapes().len(),
+            bounds.width(),
+            bounds.height(),
+            bounds.min
+        );
+    }
 
     let cardinal = Cardinal;
     let blue_jay = BlueJay;
@@ -124,9 +168,36 @@ fn main() {
     vec.print_in_option();
 
     let age = Years(5);
-    let age_days = age.to_days();
     println!("old enough {}", old_enough(&age));
-    println!("old enough {}", old_enough(&age_days.to_years()));
+    // a year is not 365 days, date.rs counts the days and the birthdays
+    let birth: Date = "2004-02-29".parse().unwrap();
+    let today = Date::new(2022, 2, 28).unwrap();
+    let age_days = today - birth;
+    println!(
+        "born on a {} {}, {} later on {} at {} days / 365 = {} years",
+        birth.weekday(),
+        birth,
+        age_days,
+        today,
+        age_days.num_days(),
+        age_days.num_days() / 365
+    );
+    if let Some(age) = date::age_on(birth, today) {
+        println!("old enough {}", old_enough(&age));
+    }
+    // without a February 29 that year the birthday is March 1
+    let birthday = Date::new(birth.year() + 18, birth.month(), birth.day())
+        .unwrap_or_else(|_| Date::new(birth.year() + 18, 3, 1).unwrap());
+    println!(
+        "old enough on {}, {} later: {}",
+        birthday,
+        birthday - today,
+        date::age_on(birth, birthday).is_some_and(|age| old_enough(&age))
+    );
+    println!(
+        "52 weeks after that: {}",
+        birthday + Duration::weeks(52).unwrap()
+    );
     //println!("old enough {}", old_enough(&age_days));
 
     // obtain base type value of newtype value
@@ -164,6 +235,29 @@ fn main() {
     println!("Last number: {}", container.last());
     println!("The difference is: {}", a_difference(&container));
 
+    // collection.rs: the same questions for any number of items, in any collection
+    let readings = vec![number_1, number_2, -4, 8];
+    let set: HashSet<i32> = readings.iter().copied().collect();
+    println!(
+        "difference: pair {:?}, vec {:?}, set {:?}",
+        collection::difference(&container),
+        collection::difference(&readings),
+        collection::difference(&set)
+    );
+    println!(
+        "span: {:?}, all within 0..=10: {}",
+        collection::span(&readings[..]),
+        collection::all_within(&readings, 0..=10)
+    );
+    // `Collection` is not imported: its `first` and `last` would clash with `Contains` above
+    let empty: Vec<i32> = Vec::new();
+    println!(
+        "the set holds {} readings, 8 among them: {}, an empty vec is empty: {}",
+        collection::Collection::len(&set),
+        collection::Collection::contains(&set, &8),
+        collection::Collection::is_empty(&empty)
+    );
+
     let _tuple1: PhantomTuple<char, f32> = PhantomTuple('Q', PhantomData);
     let _tuple2: PhantomTuple<char, f64> = PhantomTuple('Q', PhantomData);
 
@@ -180,6 +274,31 @@ fn main() {
     // println!("_tuple1 == _tuple2 yields: {}", _tuple1 == _tuple2); // expected f32, found f64
     // println!("_struct1 == _struct2 yields: {}", _struct1 == _struct2);
 
+    // typestate.rs puts a state in the phantom: only an open connection has `send`
+    let configured = Connection::new()
+        .host("example.org")
+        .timeout(std::time::Duration::from_secs(5))
+        .header("Accept", "text/plain");
+    println!("{} to {}", configured.state(), configured.address());
+    let mut open = configured.open().expect("host and port are set");
+    let sent = open.send("GET /");
+    println!("{}: sent {} bytes", open.state(), sent);
+    let closed = open.close();
+    println!("{}: {:?}", closed.state(), closed.transcript());
+    // no method `send` found for struct `Connection<Closed>`
+    // closed.send("GET /");
+    match closed.reconfigure().port(0).open() {
+        Ok(_) => println!("opened on port 0"),
+        Err(err) => println!("reopening failed: {}", err),
+    }
+    println!(
+        "size of Settings: {}, Connection<Unconfigured>: {}, Connection<Open>: {}, Connection<Closed>: {}",
+        std::mem::size_of::<Settings>(),
+        std::mem::size_of::<Connection<Unconfigured>>(),
+        std::mem::size_of::<Connection<Open>>(),
+        std::mem::size_of::<Connection<Closed>>()
+    );
+
     let one_foot: Length<Inch> = Length(12.0, PhantomData);
     let one_meter: Length<Mm> = Length(1000.0, PhantomData);
 
@@ -190,6 +309,25 @@ fn main() {
 
     // type mismatch
     //let one_feter = one_foot + one_meter;
+
+    // units.rs goes further: converting, and dimensions derived by multiplying and dividing
+    let one_foot = Quantity::<units::Inch>::new(12.0);
+    println!(
+        "one foot = {} = {:.1}",
+        one_foot,
+        one_foot.to::<units::Mm>()
+    );
+    let room = Quantity::<Metre>::new(4.0) * Quantity::<Metre>::new(2.5);
+    println!("room: {} = {:.0}", room, room.to::<Prod<Foot, Foot>>());
+    let speed = Quantity::<Km>::new(42.195) / Quantity::<Hour>::new(2.0);
+    let in_si: Quantity<Quot<Metre, Second>> = speed.to();
+    println!("marathon pace: {:.2} = {:.2}", speed, in_si);
+    let hour: Quantity<Second> = (Quantity::<Km>::new(42.195) / in_si).to();
+    println!("the same distance at that speed takes {}", hour);
+    let weight = Quantity::<Pound>::new(150.0).to::<Kg>();
+    println!("150 lb = {:.1}", weight);
+    // type mismatch: NanoSecond + Inch, which the u64 aliases in types allow
+    // let nonsense = Quantity::<units::NanoSecond>::new(5.0) + one_foot;
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -288,19 +426,6 @@ where
 
 // new type idiom, gives compile time guarantees that the right type of value match
 struct Years(i64);
-struct Days(i64);
-
-impl Years {
-    pub fn to_days(&self) -> Days {
-        Days(self.0 * 365)
-    }
-}
-
-impl Days {
-    pub fn to_years(&self) -> Years {
-        Years(self.0 / 365)
-    }
-}
 
 fn old_enough(age: &Years) -> bool {
     age.0 >= 18
@@ -381,10 +506,11 @@ struct Rectangle {
     height: f64,
 }
 
-#[allow(dead_code)]
+// its corners, shapes.rs measures it
 struct Triangle {
-    length: f64,
-    height: f64,
+    a: Point,
+    b: Point,
+    c: Point,
 }
 
 fn print_debug<T: Debug>(t: &T) {
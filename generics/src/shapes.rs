@@ -0,0 +1,527 @@
+// the shapes `HasArea` left out, and `Shape` for what else a shape can tell about itself.
+// `Shape` builds on `HasArea`, so anything that is a `Shape` still works with `area(&t)`, and
+// `Box<dyn Shape>` can hold any of them in one `Scene`.
+// `Rectangle` and `Circle` carry no position, they sit with their corner and centre at the
+// origin. the circle is conversion's, shared through its file.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::circle::Circle;
+use super::{HasArea, Rectangle, Triangle};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn distance(&self, other: &Point) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "({:.*}, {:.*})", p, self.x, p, self.y),
+            None => write!(f, "({}, {})", self.x, self.y),
+        }
+    }
+}
+
+// the smallest axis aligned box around a shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    // `None` without any points
+    pub fn around<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<BoundingBox> {
+        points.into_iter().fold(None, |bounds, p| {
+            let point = BoundingBox { min: *p, max: *p };
+            Some(bounds.map_or(point, |b: BoundingBox| b.union(&point)))
+        })
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max.y - self.min.y
+    }
+}
+
+pub trait Shape: HasArea + fmt::Display {
+    fn perimeter(&self) -> f64;
+    // the centre of mass of the area
+    fn centroid(&self) -> Point;
+    fn bounding_box(&self) -> BoundingBox;
+}
+
+impl Shape for Rectangle {
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.length + self.height)
+    }
+
+    fn centroid(&self) -> Point {
+        Point::new(self.length / 2.0, self.height / 2.0)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(0.0, 0.0),
+            max: Point::new(self.length, self.height),
+        }
+    }
+}
+
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rectangle of {} by {}", self.length, self.height)
+    }
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point) -> Triangle {
+        Triangle { a, b, c }
+    }
+
+    fn sides(&self) -> [f64; 3] {
+        [
+            self.a.distance(&self.b),
+            self.b.distance(&self.c),
+            self.c.distance(&self.a),
+        ]
+    }
+}
+
+// Heron's formula, from the three sides and half the perimeter
+impl HasArea for Triangle {
+    fn area(&self) -> f64 {
+        let [a, b, c] = self.sides();
+        let s = (a + b + c) / 2.0;
+        // rounding can take a flat triangle just below zero
+        (s * (s - a) * (s - b) * (s - c)).max(0.0).sqrt()
+    }
+}
+
+impl Shape for Triangle {
+    fn perimeter(&self) -> f64 {
+        self.sides().iter().sum()
+    }
+
+    fn centroid(&self) -> Point {
+        Point::new(
+            (self.a.x + self.b.x + self.c.x) / 3.0,
+            (self.a.y + self.b.y + self.c.y) / 3.0,
+        )
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&[self.a, self.b, self.c]).unwrap()
+    }
+}
+
+impl fmt::Display for Triangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Triangle {}, {}, {}", self.a, self.b, self.c)
+    }
+}
+
+// conversion's radius is an i32 that can be negative, its size is what counts.
+// taken in f64, where even `i32::MIN` has an absolute value
+impl Circle {
+    fn size(&self) -> f64 {
+        f64::from(self.radius).abs()
+    }
+}
+
+impl HasArea for Circle {
+    fn area(&self) -> f64 {
+        PI * self.size().powi(2)
+    }
+}
+
+impl Shape for Circle {
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.size()
+    }
+
+    fn centroid(&self) -> Point {
+        Point::new(0.0, 0.0)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let r = self.size();
+        BoundingBox {
+            min: Point::new(-r, -r),
+            max: Point::new(r, r),
+        }
+    }
+}
+
+// `sides` corners on a circle of `radius` around `center`, the first one straight right of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegularPolygon {
+    center: Point,
+    sides: u32,
+    radius: f64,
+}
+
+impl RegularPolygon {
+    // `None` for fewer than three sides, or a radius that is not a positive number
+    pub fn new(center: Point, sides: u32, radius: f64) -> Option<RegularPolygon> {
+        if sides < 3 || !radius.is_finite() || radius <= 0.0 {
+            return None;
+        }
+        Some(RegularPolygon {
+            center,
+            sides,
+            radius,
+        })
+    }
+
+    pub fn vertices(&self) -> Vec<Point> {
+        (0..self.sides)
+            .map(|i| {
+                let angle = 2.0 * PI * f64::from(i) / f64::from(self.sides);
+                Point::new(
+                    self.center.x + self.radius * angle.cos(),
+                    self.center.y + self.radius * angle.sin(),
+                )
+            })
+            .collect()
+    }
+}
+
+// `sides` triangles meeting in the centre
+impl HasArea for RegularPolygon {
+    fn area(&self) -> f64 {
+        let n = f64::from(self.sides);
+        n / 2.0 * self.radius.powi(2) * (2.0 * PI / n).sin()
+    }
+}
+
+impl Shape for RegularPolygon {
+    fn perimeter(&self) -> f64 {
+        let n = f64::from(self.sides);
+        2.0 * n * self.radius * (PI / n).sin()
+    }
+
+    fn centroid(&self) -> Point {
+        self.center
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&self.vertices()).unwrap()
+    }
+}
+
+impl fmt::Display for RegularPolygon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Regular {}-gon of radius {} around {}",
+            self.sides, self.radius, self.center
+        )
+    }
+}
+
+// any simple polygon: its edges may not cross, which is up to the caller, the shoelace formula
+// does not check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    // `None` for fewer than three vertices or no area at all
+    pub fn new(vertices: Vec<Point>) -> Option<Polygon> {
+        let polygon = Polygon { vertices };
+        if polygon.vertices.len() < 3 || polygon.signed_area() == 0.0 {
+            None
+        } else {
+            Some(polygon)
+        }
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+    }
+
+    // the shoelace formula, positive when the vertices go counterclockwise
+    fn signed_area(&self) -> f64 {
+        self.edges()
+            .map(|(p, q)| p.x * q.y - q.x * p.y)
+            .sum::<f64>()
+            / 2.0
+    }
+}
+
+impl HasArea for Polygon {
+    fn area(&self) -> f64 {
+        self.signed_area().abs()
+    }
+}
+
+impl Shape for Polygon {
+    fn perimeter(&self) -> f64 {
+        self.edges().map(|(p, q)| p.distance(q)).sum()
+    }
+
+    // the centroids of the triangles each edge makes with the origin, weighted by their
+    // signed areas
+    fn centroid(&self) -> Point {
+        let (x, y) = self.edges().fold((0.0, 0.0), |(x, y), (p, q)| {
+            let cross = p.x * q.y - q.x * p.y;
+            (x + (p.x + q.x) * cross, y + (p.y + q.y) * cross)
+        });
+        let six_areas = 6.0 * self.signed_area();
+        Point::new(x / six_areas, y / six_areas)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::around(&self.vertices).unwrap()
+    }
+}
+
+impl fmt::Display for Polygon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Polygon of {} vertices", self.vertices.len())
+    }
+}
+
+// shapes of any kind together
+#[derive(Default)]
+pub struct Scene {
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene::default()
+    }
+
+    pub fn add(mut self, shape: impl Shape + 'static) -> Scene {
+        self.shapes.push(Box::new(shape));
+        self
+    }
+
+    pub fn shapes(&self) -> &[Box<dyn Shape>] {
+        &self.shapes
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().map(|s| s.area()).sum()
+    }
+
+    // the first of the largest when several are as large
+    pub fn largest(&self) -> Option<&dyn Shape> {
+        self.shapes
+            .iter()
+            .map(|s| s.as_ref())
+            .fold(None, |largest: Option<&dyn Shape>, s| match largest {
+                Some(l) if l.area() >= s.area() => Some(l),
+                _ => Some(s),
+            })
+    }
+
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.shapes
+            .iter()
+            .map(|s| s.bounding_box())
+            .reduce(|a, b| a.union(&b))
+    }
+}
+
+// one line a shape, then the total and the largest one
+impl fmt::Display for Scene {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for shape in &self.shapes {
+            writeln!(
+                f,
+                "{}: area {:.2}, perimeter {:.2}, centroid {:.2}",
+                shape,
+                shape.area(),
+                shape.perimeter(),
+                shape.centroid()
+            )?;
+        }
+        write!(f, "total area {:.2}", self.total_area())?;
+        if let Some(largest) = self.largest() {
+            write!(f, ", largest: {}", largest)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    fn assert_close_point(p: Point, q: Point) {
+        assert!(close(p.x, q.x) && close(p.y, q.y), "{} != {}", p, q);
+    }
+
+    fn right_triangle() -> Triangle {
+        Triangle::new(
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 0.0),
+            Point::new(0.0, 4.0),
+        )
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let r = Rectangle {
+            length: 3.0,
+            height: 4.0,
+        };
+        assert_eq!(crate::area(&r), 12.0);
+        assert_eq!(r.perimeter(), 14.0);
+        assert_eq!(r.centroid(), Point::new(1.5, 2.0));
+        assert_eq!(r.bounding_box().width(), 3.0);
+        assert_eq!(r.to_string(), "Rectangle of 3 by 4");
+    }
+
+    #[test]
+    fn test_triangle_heron() {
+        let t = right_triangle();
+        assert!(close(crate::area(&t), 6.0));
+        assert!(close(t.perimeter(), 12.0));
+        assert_close_point(t.centroid(), Point::new(1.0, 4.0 / 3.0));
+        assert_eq!(
+            t.bounding_box(),
+            BoundingBox {
+                min: Point::new(0.0, 0.0),
+                max: Point::new(3.0, 4.0)
+            }
+        );
+        // the same area as the shoelace formula gives
+        let as_polygon = Polygon::new(vec![t.a, t.b, t.c]).unwrap();
+        assert!(close(as_polygon.area(), t.area()));
+        assert_close_point(as_polygon.centroid(), t.centroid());
+        // all three corners on a line
+        let flat = Triangle::new(
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(3.0, 3.0),
+        );
+        assert!(flat.area() < 1e-6);
+    }
+
+    #[test]
+    fn test_circle() {
+        let c = Circle { radius: 2 };
+        assert!(close(c.area(), 4.0 * PI));
+        assert!(close(c.perimeter(), 4.0 * PI));
+        assert_eq!(c.centroid(), Point::new(0.0, 0.0));
+        assert_eq!(c.bounding_box().height(), 4.0);
+        assert_eq!(c.to_string(), "Circle of radius 2");
+
+        let negative = Circle { radius: -2 };
+        assert_eq!(negative.area(), c.area());
+        assert_eq!(negative.perimeter(), c.perimeter());
+        assert_eq!(negative.bounding_box(), c.bounding_box());
+        let smallest = Circle { radius: i32::MIN };
+        assert_eq!(smallest.bounding_box().max.x, 2f64.powi(31));
+        assert!(smallest.perimeter() > 0.0);
+    }
+
+    #[test]
+    fn test_regular_polygon() {
+        assert!(RegularPolygon::new(Point::new(0.0, 0.0), 2, 1.0).is_none());
+        for radius in [0.0, -1.0, f64::NAN, f64::INFINITY].iter() {
+            assert!(RegularPolygon::new(Point::new(0.0, 0.0), 5, *radius).is_none());
+        }
+        // a square with its corners on the axes: the diagonal is 2, the side sqrt(2)
+        let square = RegularPolygon::new(Point::new(1.0, 1.0), 4, 1.0).unwrap();
+        assert!(close(square.area(), 2.0));
+        assert!(close(square.perimeter(), 4.0 * 2f64.sqrt()));
+        assert!(close(square.bounding_box().width(), 2.0));
+        // it agrees with the polygon of its vertices
+        let hexagon = RegularPolygon::new(Point::new(-2.0, 5.0), 6, 3.0).unwrap();
+        let polygon = Polygon::new(hexagon.vertices()).unwrap();
+        assert!(close(hexagon.area(), polygon.area()));
+        assert!(close(hexagon.perimeter(), polygon.perimeter()));
+        assert_close_point(hexagon.centroid(), polygon.centroid());
+        // many sides come close to the circle
+        let many = RegularPolygon::new(Point::new(0.0, 0.0), 10_000, 1.0).unwrap();
+        assert!((many.area() - PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_polygon_shoelace() {
+        assert!(Polygon::new(vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)]).is_none());
+        let line = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 2.0),
+        ];
+        assert!(Polygon::new(line).is_none());
+        // an L, clockwise: the area does not depend on the direction
+        let l = Polygon::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 3.0),
+            Point::new(1.0, 3.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 1.0),
+            Point::new(2.0, 0.0),
+        ])
+        .unwrap();
+        assert!(close(l.area(), 4.0));
+        assert!(close(l.perimeter(), 10.0));
+        // a 1x3 bar and a 1x1 square: (3 * (0.5, 1.5) + (1.5, 0.5)) / 4
+        assert_close_point(l.centroid(), Point::new(0.75, 1.25));
+        assert_eq!(l.bounding_box().max, Point::new(2.0, 3.0));
+    }
+
+    #[test]
+    fn test_scene() {
+        assert!(Scene::new().largest().is_none());
+        assert!(Scene::new().bounding_box().is_none());
+        let scene = Scene::new()
+            .add(Rectangle {
+                length: 3.0,
+                height: 4.0,
+            })
+            .add(right_triangle())
+            .add(Circle { radius: 2 })
+            .add(RegularPolygon::new(Point::new(10.0, 0.0), 3, 1.0).unwrap());
+        assert_eq!(scene.shapes().len(), 4);
+        let total = 12.0 + 6.0 + 4.0 * PI + 3.0 * 3f64.sqrt() / 4.0;
+        assert!(close(scene.total_area(), total));
+        assert_eq!(scene.largest().unwrap().to_string(), "Circle of radius 2");
+        let bounds = scene.bounding_box().unwrap();
+        assert_eq!(bounds.min, Point::new(-2.0, -2.0));
+        assert_eq!(bounds.max.x, 11.0);
+        let report = scene.to_string();
+        assert!(report.starts_with("Rectangle of 3 by 4: area 12.00, perimeter 14.00"));
+        assert!(report.ends_with(&format!(
+            "total area {:.2}, largest: Circle of radius 2",
+            total
+        )));
+    }
+}
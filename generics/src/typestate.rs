@@ -0,0 +1,254 @@
+// a connection builder that only has the methods of the state it is in.
+// the state is a type parameter held in a `PhantomData`, like `B` in `PhantomTuple<A, B>`:
+//
+//     Connection<Unconfigured> --host--> Configured --open--> Open --close--> Closed
+//                                            ^                                  |
+//                                            +------------reconfigure-----------+
+//
+// every transition takes `self` by value, so the connection in the old state is gone, and
+// `send` on a connection that is not open, or `open` before a host is set, does not compile.
+// the states take no memory, a `Connection` is as large as its settings whatever its state.
+// tests/compile_fail has the illegal transitions, it includes this file too.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+// the states, only ever used as types
+pub enum Unconfigured {}
+pub enum Configured {}
+pub enum Open {}
+pub enum Closed {}
+
+pub trait State {
+    const NAME: &'static str;
+}
+
+impl State for Unconfigured {
+    const NAME: &'static str = "unconfigured";
+}
+
+impl State for Configured {
+    const NAME: &'static str = "configured";
+}
+
+impl State for Open {
+    const NAME: &'static str = "open";
+}
+
+impl State for Closed {
+    const NAME: &'static str = "closed";
+}
+
+// what a connection knows in any state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    host: String,
+    port: u16,
+    timeout: Option<Duration>,
+    headers: Vec<(String, String)>,
+    sent: Vec<String>,
+}
+
+pub struct Connection<S> {
+    settings: Settings,
+    state: PhantomData<S>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpenError {
+    EmptyHost,
+    PortZero,
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OpenError::EmptyHost => write!(f, "no host to connect to"),
+            OpenError::PortZero => write!(f, "port 0 cannot be connected to"),
+        }
+    }
+}
+
+impl<S> Connection<S> {
+    // the same settings in another state
+    fn into_state<T>(self) -> Connection<T> {
+        Connection {
+            settings: self.settings,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<S: State> Connection<S> {
+    pub fn state(&self) -> &'static str {
+        S::NAME
+    }
+}
+
+impl<S: State> fmt::Debug for Connection<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("state", &S::NAME)
+            .field("settings", &self.settings)
+            .finish()
+    }
+}
+
+impl Default for Connection<Unconfigured> {
+    fn default() -> Self {
+        Connection {
+            settings: Settings {
+                host: String::new(),
+                port: 80,
+                timeout: None,
+                headers: Vec::new(),
+                sent: Vec::new(),
+            },
+            state: PhantomData,
+        }
+    }
+}
+
+impl Connection<Unconfigured> {
+    pub fn new() -> Self {
+        Connection::default()
+    }
+
+    pub fn host(mut self, host: &str) -> Connection<Configured> {
+        self.settings.host = host.to_owned();
+        self.into_state()
+    }
+}
+
+impl Connection<Configured> {
+    pub fn port(mut self, port: u16) -> Self {
+        self.settings.port = port;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.settings.timeout = Some(timeout);
+        self
+    }
+
+    // sent with every request
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.settings
+            .headers
+            .push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    // the address to connect to, only known from here on
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.settings.host, self.settings.port)
+    }
+
+    pub fn open(self) -> Result<Connection<Open>, OpenError> {
+        if self.settings.host.is_empty() {
+            Err(OpenError::EmptyHost)
+        } else if self.settings.port == 0 {
+            Err(OpenError::PortZero)
+        } else {
+            Ok(self.into_state())
+        }
+    }
+}
+
+impl Connection<Open> {
+    // sends `request` with the headers, returns the bytes sent
+    pub fn send(&mut self, request: &str) -> usize {
+        let mut message = request.to_owned();
+        for (name, value) in &self.settings.headers {
+            message += &format!("\n{}: {}", name, value);
+        }
+        let len = message.len();
+        self.settings.sent.push(message);
+        len
+    }
+
+    pub fn close(self) -> Connection<Closed> {
+        self.into_state()
+    }
+}
+
+impl Connection<Closed> {
+    // everything sent while it was open
+    pub fn transcript(&self) -> &[String] {
+        &self.settings.sent
+    }
+
+    // the same settings, ready to open again with an empty transcript
+    pub fn reconfigure(mut self) -> Connection<Configured> {
+        self.settings.sent.clear();
+        self.into_state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::{align_of, size_of};
+
+    #[test]
+    fn test_transitions() {
+        let configured = Connection::new()
+            .host("example.org")
+            .port(8080)
+            .timeout(Duration::from_secs(5))
+            .header("Accept", "text/plain");
+        assert_eq!(configured.state(), "configured");
+        assert_eq!(configured.address(), "example.org:8080");
+
+        let mut open = configured.open().unwrap();
+        assert_eq!(open.state(), "open");
+        assert_eq!(open.send("GET /"), "GET /\nAccept: text/plain".len());
+        open.send("GET /about");
+
+        let closed = open.close();
+        assert_eq!(closed.state(), "closed");
+        assert_eq!(closed.transcript().len(), 2);
+        assert!(closed.transcript()[1].starts_with("GET /about\n"));
+
+        let again = closed.reconfigure().port(8081);
+        assert_eq!(again.address(), "example.org:8081");
+        let closed = again.open().unwrap().close();
+        assert!(closed.transcript().is_empty());
+    }
+
+    #[test]
+    fn test_open_errors() {
+        assert_eq!(
+            Connection::new().host("").open().unwrap_err(),
+            OpenError::EmptyHost
+        );
+        let err = Connection::new().host("h").port(0).open().unwrap_err();
+        assert_eq!(err.to_string(), "port 0 cannot be connected to");
+    }
+
+    #[test]
+    fn test_debug_shows_the_state() {
+        let open = Connection::new().host("h").open().unwrap();
+        let debug = format!("{:?}", open);
+        assert!(debug.starts_with("Connection { state: \"open\", settings: Settings {"));
+        assert!(debug.contains("port: 80"));
+    }
+
+    // the state costs nothing: every `Connection` is exactly its `Settings`
+    #[test]
+    fn test_zero_sized_states() {
+        assert_eq!(size_of::<PhantomData<Open>>(), 0);
+        assert_eq!(size_of::<Connection<Unconfigured>>(), size_of::<Settings>());
+        assert_eq!(size_of::<Connection<Configured>>(), size_of::<Settings>());
+        assert_eq!(size_of::<Connection<Open>>(), size_of::<Settings>());
+        assert_eq!(size_of::<Connection<Closed>>(), size_of::<Settings>());
+        assert_eq!(align_of::<Connection<Open>>(), align_of::<Settings>());
+        // nor does it cost anything once wrapped
+        assert_eq!(
+            size_of::<Result<Connection<Open>, OpenError>>(),
+            size_of::<Result<Settings, OpenError>>()
+        );
+    }
+}
@@ -0,0 +1,371 @@
+// quantities checked for their dimension and unit at compile time.
+// `Length<Unit>` in main stops `Inch + Mm` but knows nothing else. here every unit belongs to
+// a dimension (length, time, mass, or one derived from them), adding needs the same unit,
+// converting needs the same dimension, and multiplying or dividing two quantities gives a
+// quantity of the derived dimension, `Metre * Metre` is an area and `Km / Hour` a velocity.
+// the value is kept in the unit of the type, converting scales it through the SI base unit.
+// tests/compile_fail has the mismatches that must not compile, it includes this file too.
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// the dimensions, only ever used as types
+pub enum Ratio {}
+pub enum Length {}
+pub enum Time {}
+pub enum Mass {}
+pub enum Area {}
+pub enum Velocity {}
+
+// the dimension of a product or a quotient, only defined where it makes sense
+pub trait DimMul<Rhs> {
+    type Output;
+}
+
+pub trait DimDiv<Rhs> {
+    type Output;
+}
+
+macro_rules! dimension_table {
+    (@impl $a:ident * $b:ident = $product:ident) => {
+        impl DimMul<$b> for $a {
+            type Output = $product;
+        }
+    };
+    (@impl $a:ident / $b:ident = $quotient:ident) => {
+        impl DimDiv<$b> for $a {
+            type Output = $quotient;
+        }
+    };
+    ($($a:ident $op:tt $b:ident = $result:ident;)*) => {
+        $(dimension_table!(@impl $a $op $b = $result);)*
+    };
+}
+
+dimension_table! {
+    Length * Length = Area;
+    Velocity * Time = Length;
+    Time * Velocity = Length;
+    Ratio * Ratio = Ratio;
+    Ratio * Length = Length;
+    Ratio * Time = Time;
+    Ratio * Mass = Mass;
+    Ratio * Area = Area;
+    Ratio * Velocity = Velocity;
+    Length * Ratio = Length;
+    Time * Ratio = Time;
+    Mass * Ratio = Mass;
+    Area * Ratio = Area;
+    Velocity * Ratio = Velocity;
+
+    Length / Time = Velocity;
+    Length / Velocity = Time;
+    Area / Length = Length;
+    Ratio / Ratio = Ratio;
+    Length / Length = Ratio;
+    Time / Time = Ratio;
+    Mass / Mass = Ratio;
+    Area / Area = Ratio;
+    Velocity / Velocity = Ratio;
+    Length / Ratio = Length;
+    Time / Ratio = Time;
+    Mass / Ratio = Mass;
+    Area / Ratio = Area;
+    Velocity / Ratio = Velocity;
+}
+
+pub trait Unit {
+    type Dim;
+    // how many SI base units (m, s, kg) one of this unit is
+    const FACTOR: f64;
+
+    fn symbol() -> String;
+}
+
+macro_rules! units {
+    ($($name:ident: $dim:ident, $factor:expr, $symbol:expr;)*) => {
+        $(pub enum $name {}
+
+        impl Unit for $name {
+            type Dim = $dim;
+            const FACTOR: f64 = $factor;
+
+            fn symbol() -> String {
+                $symbol.to_owned()
+            }
+        })*
+    };
+}
+
+units! {
+    One: Ratio, 1.0, "";
+    NanoSecond: Time, 1e-9, "ns";
+    Second: Time, 1.0, "s";
+    Hour: Time, 3600.0, "h";
+    Mm: Length, 0.001, "mm";
+    Inch: Length, 0.0254, "in";
+    Foot: Length, 0.3048, "ft";
+    Metre: Length, 1.0, "m";
+    Km: Length, 1000.0, "km";
+    Gram: Mass, 0.001, "g";
+    Kg: Mass, 1.0, "kg";
+    Pound: Mass, 0.453_592_37, "lb";
+}
+
+// the unit of `A` times `B`, like `Prod<Metre, Metre>` for m²
+pub struct Prod<A, B>(PhantomData<(A, B)>);
+
+impl<A: Unit, B: Unit> Unit for Prod<A, B>
+where
+    A::Dim: DimMul<B::Dim>,
+{
+    type Dim = <A::Dim as DimMul<B::Dim>>::Output;
+    const FACTOR: f64 = A::FACTOR * B::FACTOR;
+
+    fn symbol() -> String {
+        let (a, b) = (A::symbol(), B::symbol());
+        if b.is_empty() {
+            a
+        } else if a.is_empty() {
+            b
+        } else if a == b {
+            format!("{}²", a)
+        } else {
+            format!("{}·{}", a, b)
+        }
+    }
+}
+
+// the unit of `A` per `B`, like `Quot<Km, Hour>` for km/h
+pub struct Quot<A, B>(PhantomData<(A, B)>);
+
+impl<A: Unit, B: Unit> Unit for Quot<A, B>
+where
+    A::Dim: DimDiv<B::Dim>,
+{
+    type Dim = <A::Dim as DimDiv<B::Dim>>::Output;
+    const FACTOR: f64 = A::FACTOR / B::FACTOR;
+
+    fn symbol() -> String {
+        format!("{}/{}", A::symbol(), B::symbol())
+    }
+}
+
+pub struct Quantity<U> {
+    value: f64,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit> Quantity<U> {
+    pub fn new(value: f64) -> Self {
+        Quantity {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    // in the unit of the type
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // the same amount in another unit of the same dimension
+    pub fn to<V: Unit<Dim = U::Dim>>(self) -> Quantity<V> {
+        Quantity::new(self.value * U::FACTOR / V::FACTOR)
+    }
+}
+
+// derived by hand: `#[derive]` would ask the unit, which is never constructed, to be `Copy` too
+impl<U> Clone for Quantity<U> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<U> Copy for Quantity<U> {}
+
+impl<U> PartialEq for Quantity<U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<U> PartialOrd for Quantity<U> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<U: Unit> Add for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Quantity::new(self.value + rhs.value)
+    }
+}
+
+impl<U: Unit> Sub for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Quantity::new(self.value - rhs.value)
+    }
+}
+
+impl<U: Unit> Neg for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn neg(self) -> Self::Output {
+        Quantity::new(-self.value)
+    }
+}
+
+// scaling by a plain number keeps the unit
+impl<U: Unit> Mul<f64> for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Quantity::new(self.value * rhs)
+    }
+}
+
+impl<U: Unit> Div<f64> for Quantity<U> {
+    type Output = Quantity<U>;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Quantity::new(self.value / rhs)
+    }
+}
+
+impl<U, V> Mul<Quantity<V>> for Quantity<U>
+where
+    U: Unit,
+    V: Unit,
+    U::Dim: DimMul<V::Dim>,
+{
+    type Output = Quantity<Prod<U, V>>;
+
+    fn mul(self, rhs: Quantity<V>) -> Self::Output {
+        Quantity::new(self.value * rhs.value)
+    }
+}
+
+impl<U, V> Div<Quantity<V>> for Quantity<U>
+where
+    U: Unit,
+    V: Unit,
+    U::Dim: DimDiv<V::Dim>,
+{
+    type Output = Quantity<Quot<U, V>>;
+
+    fn div(self, rhs: Quantity<V>) -> Self::Output {
+        Quantity::new(self.value / rhs.value)
+    }
+}
+
+// the value honours width and precision, the unit symbol follows it
+impl<U: Unit> fmt::Display for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)?;
+        let symbol = U::symbol();
+        if symbol.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " {}", symbol)
+        }
+    }
+}
+
+impl<U: Unit> fmt::Debug for Quantity<U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Quantity({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let foot = Quantity::<Inch>::new(12.0);
+        assert_close(foot.to::<Mm>().value(), 304.8);
+        assert_close(foot.to::<Foot>().value(), 1.0);
+        assert_close(Quantity::<Km>::new(1.5).to::<Metre>().value(), 1500.0);
+        assert_close(
+            Quantity::<Hour>::new(1.0).to::<NanoSecond>().value(),
+            3.6e12,
+        );
+        assert_close(Quantity::<Pound>::new(1.0).to::<Gram>().value(), 453.592_37);
+        // there and back again
+        let mm = Quantity::<Mm>::new(42.0);
+        assert_close(mm.to::<Inch>().to::<Mm>().value(), 42.0);
+    }
+
+    #[test]
+    fn test_arithmetic_in_one_unit() {
+        let a = Quantity::<Metre>::new(3.0);
+        let b = Quantity::<Metre>::new(1.5);
+        assert_eq!(a + b, Quantity::new(4.5));
+        assert_eq!(a - b, Quantity::new(1.5));
+        assert_eq!(-a * 2.0, Quantity::new(-6.0));
+        assert_eq!(a / 4.0, Quantity::new(0.75));
+        assert!(b < a);
+        // different units of one dimension add after converting
+        let total = Quantity::<Foot>::new(1.0).to::<Inch>() + Quantity::<Inch>::new(1.0);
+        assert_close(total.value(), 13.0);
+    }
+
+    #[test]
+    fn test_derived_dimensions() {
+        let area = Quantity::<Metre>::new(2.0) * Quantity::<Metre>::new(3.0);
+        assert_close(area.value(), 6.0);
+        let in_mm: Quantity<Prod<Mm, Mm>> = area.to();
+        assert_close(in_mm.value(), 6e6);
+        // an area divided by a length is a length again
+        let side: Quantity<Metre> = (area / Quantity::<Metre>::new(3.0)).to();
+        assert_close(side.value(), 2.0);
+
+        let speed = Quantity::<Km>::new(100.0) / Quantity::<Hour>::new(2.0);
+        assert_close(speed.value(), 50.0);
+        let si: Quantity<Quot<Metre, Second>> = speed.to();
+        assert_close(si.value(), 50_000.0 / 3600.0);
+        let distance: Quantity<Km> = (speed * Quantity::<Second>::new(72.0)).to();
+        assert_close(distance.value(), 1.0);
+        let time: Quantity<Second> = (Quantity::<Metre>::new(10.0) / si).to();
+        assert_close(time.value(), 0.72);
+
+        // a unit divided by one of its own dimension is a plain ratio
+        let ratio: Quantity<One> = (Quantity::<Foot>::new(1.0) / Quantity::<Inch>::new(1.0)).to();
+        assert_close(ratio.value(), 12.0);
+        let heavier = Quantity::<Kg>::new(2.0) / Quantity::<Pound>::new(1.0);
+        assert!(heavier.to::<One>().value() > 4.0);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Quantity::<Inch>::new(12.0).to_string(), "12 in");
+        assert_eq!(
+            format!("{:.1}", Quantity::<Inch>::new(12.0).to::<Mm>()),
+            "304.8 mm"
+        );
+        let area = Quantity::<Metre>::new(2.0) * Quantity::<Metre>::new(3.0);
+        assert_eq!(area.to_string(), "6 m²");
+        let speed = Quantity::<Km>::new(90.0) / Quantity::<Hour>::new(1.0);
+        assert_eq!(speed.to_string(), "90 km/h");
+        let doubled = Quantity::<Kg>::new(1.0) * Quantity::<One>::new(2.0);
+        assert_eq!(format!("{:>4}", doubled), "   2 kg");
+        assert_eq!(Quantity::<One>::new(0.5).to_string(), "0.5");
+        assert_eq!(
+            format!("{:?}", Quantity::<NanoSecond>::new(5.0)),
+            "Quantity(5 ns)"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+// what units.rs and typestate.rs have to reject, as programs that must not compile.
+// every file in tests/compile_fail includes one of them and starts with the outcome it expects:
+// `// error[E0308]: why` for a rejected program, `// ok: why` for one that has to build.
+// `rustc` only checks them (`--emit=metadata`), nothing is linked or run.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn test_compile_fail() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compile_fail");
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("compile_fail");
+    let mut checked = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        let source = fs::read_to_string(&path).unwrap();
+        let first_line = source.lines().next().unwrap_or("");
+        let expected = first_line
+            .strip_prefix("// ")
+            .and_then(|rest| rest.split(':').next())
+            .unwrap_or_else(|| panic!("{} does not say what to expect", path.display()));
+
+        let output = Command::new("rustc")
+            .args([
+                "--edition",
+                "2018",
+                "--crate-type",
+                "bin",
+                "--emit=metadata",
+            ])
+            .arg("--out-dir")
+            .arg(&out_dir)
+            .arg(&path)
+            .output()
+            .expect("rustc is not on the PATH");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if expected == "ok" {
+            assert!(
+                output.status.success(),
+                "{} should compile:\n{}",
+                path.display(),
+                stderr
+            );
+        } else {
+            assert!(
+                !output.status.success(),
+                "{} should not compile",
+                path.display()
+            );
+            assert!(
+                stderr.contains(expected),
+                "{} should fail with {}:\n{}",
+                path.display(),
+                expected,
+                stderr
+            );
+        }
+        checked += 1;
+    }
+    assert!(checked > 1, "no programs in {}", dir.display());
+}
@@ -0,0 +1,9 @@
+// error[E0308]: units of one dimension still have to be converted before adding
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Inch, Mm, Quantity};
+
+fn main() {
+    let _ = Quantity::<Inch>::new(1.0) + Quantity::<Mm>::new(1.0);
+}
@@ -0,0 +1,9 @@
+// error[E0308]: the `NanoSecond + Inch` the u64 aliases in types let through
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Inch, NanoSecond, Quantity};
+
+fn main() {
+    let _ = Quantity::<NanoSecond>::new(5.0) + Quantity::<Inch>::new(2.0);
+}
@@ -0,0 +1,10 @@
+// error[E0308]: a product has the derived dimension, not the one it was made of
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Metre, Quantity};
+
+fn main() {
+    let side = Quantity::<Metre>::new(2.0);
+    let _: Quantity<Metre> = side * side;
+}
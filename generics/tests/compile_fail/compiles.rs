@@ -0,0 +1,12 @@
+// ok: the control case, so a broken path or harness cannot pass for a caught mismatch
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Hour, Inch, Km, Metre, Mm, Quantity, Quot};
+
+fn main() {
+    let length = Quantity::<Inch>::new(1.0).to::<Mm>() + Quantity::<Mm>::new(1.0);
+    let _area = length * length;
+    let _speed: Quantity<Quot<Km, Hour>> =
+        (Quantity::<Metre>::new(1.0) / Quantity::<Hour>::new(1.0)).to();
+}
@@ -0,0 +1,9 @@
+// error[E0271]: converting needs the same dimension
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Inch, Quantity, Second};
+
+fn main() {
+    let _ = Quantity::<Inch>::new(1.0).to::<Second>();
+}
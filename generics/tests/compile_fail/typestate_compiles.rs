@@ -0,0 +1,17 @@
+// ok: every transition in order, so the other typestate files fail for their own reason
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::{Configured, Connection};
+
+fn main() {
+    let mut open = Connection::new()
+        .host("example.org")
+        .port(8080)
+        .open()
+        .unwrap();
+    open.send("GET /");
+    let closed = open.close();
+    let _: &[String] = closed.transcript();
+    let _: Connection<Configured> = closed.reconfigure();
+}
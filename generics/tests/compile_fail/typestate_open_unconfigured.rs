@@ -0,0 +1,9 @@
+// error[E0599]: there is no `open` until a host has been set
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::Connection;
+
+fn main() {
+    let _ = Connection::new().port(8080).open();
+}
@@ -0,0 +1,11 @@
+// error[E0599]: a closed connection cannot send any more
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::Connection;
+
+fn main() {
+    let open = Connection::new().host("example.org").open().unwrap();
+    let mut closed = open.close();
+    closed.send("GET /");
+}
@@ -0,0 +1,10 @@
+// error[E0599]: a configured connection cannot send yet
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::Connection;
+
+fn main() {
+    let mut configured = Connection::new().host("example.org");
+    configured.send("GET /");
+}
@@ -0,0 +1,11 @@
+// error[E0382]: closing moves the open connection, the old binding cannot send
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::Connection;
+
+fn main() {
+    let mut open = Connection::new().host("example.org").open().unwrap();
+    let _closed = open.close();
+    open.send("GET /");
+}
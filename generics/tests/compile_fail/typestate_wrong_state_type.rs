@@ -0,0 +1,14 @@
+// error[E0308]: reconfiguring gives a configured connection, not an open one
+#[path = "../../src/typestate.rs"]
+mod typestate;
+
+use typestate::{Connection, Open};
+
+fn main() {
+    let closed = Connection::new()
+        .host("example.org")
+        .open()
+        .unwrap()
+        .close();
+    let _: Connection<Open> = closed.reconfigure();
+}
@@ -0,0 +1,9 @@
+// error[E0277]: mass times time is no dimension the table knows
+#[path = "../../src/units.rs"]
+mod units;
+
+use units::{Kg, Quantity, Second};
+
+fn main() {
+    let _ = Quantity::<Kg>::new(1.0) * Quantity::<Second>::new(1.0);
+}
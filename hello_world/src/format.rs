@@ -0,0 +1,177 @@
+// formatting traits beyond `Display`: `{:b}`, `{:o}`, `{:x}`, `{:X}` and `{:e}`.
+// every struct writes its punctuation itself and hands each field to a field formatter
+// together with the caller's `Formatter`, so width, fill, precision and sign flags apply to
+// every field instead of being dropped, e.g. `{:>4}` turns `(1, 2)` into `(   1,    2)`.
+// floats have no `Binary`/`Octal`/`LowerHex`/`UpperHex`, those print the IEEE-754 bits.
+
+use std::fmt;
+use std::fmt::Formatter;
+
+use super::{Color, Complex, MinMax, Point, Point2D};
+
+type FieldFmt<T> = fn(&T, &mut Formatter<'_>) -> fmt::Result;
+
+impl Point {
+    pub fn fmt_fields(&self, f: &mut Formatter<'_>, field: FieldFmt<i32>) -> fmt::Result {
+        f.write_str("(")?;
+        field(&self.x, f)?;
+        f.write_str(", ")?;
+        field(&self.y, f)?;
+        f.write_str(")")
+    }
+}
+
+impl MinMax {
+    pub fn fmt_fields(&self, f: &mut Formatter<'_>, field: FieldFmt<i64>) -> fmt::Result {
+        f.write_str("(")?;
+        field(&self.0, f)?;
+        f.write_str(", ")?;
+        field(&self.1, f)?;
+        f.write_str(")")
+    }
+}
+
+impl Point2D {
+    pub fn fmt_fields(&self, f: &mut Formatter<'_>, field: FieldFmt<f64>) -> fmt::Result {
+        f.write_str("x: ")?;
+        field(&self.x, f)?;
+        f.write_str(", y: ")?;
+        field(&self.y, f)
+    }
+}
+
+impl Complex {
+    pub fn fmt_fields(&self, f: &mut Formatter<'_>, field: FieldFmt<f64>) -> fmt::Result {
+        field(&self.re, f)?;
+        f.write_str(" + ")?;
+        field(&self.im, f)?;
+        f.write_str("i")
+    }
+}
+
+impl Color {
+    // the trailing hex code is always written as plain `0xrrggbb`
+    pub fn fmt_fields(&self, f: &mut Formatter<'_>, field: FieldFmt<u8>) -> fmt::Result {
+        f.write_str("RGB (")?;
+        field(&self.red, f)?;
+        f.write_str(", ")?;
+        field(&self.green, f)?;
+        f.write_str(", ")?;
+        field(&self.blue, f)?;
+        write!(f, ") 0x{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+// implement each listed `fmt` trait by forwarding it to the fields
+macro_rules! forward_fmt {
+    ($ty:ty => $($trait:ident),+) => {
+        $(
+            impl fmt::$trait for $ty {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    self.fmt_fields(f, fmt::$trait::fmt)
+                }
+            }
+        )+
+    };
+}
+
+// the same for float structs, where the integer radix traits see `f64::to_bits`
+macro_rules! forward_fmt_bits {
+    ($ty:ty => $($trait:ident),+) => {
+        $(
+            impl fmt::$trait for $ty {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    self.fmt_fields(f, |v, f| fmt::$trait::fmt(&v.to_bits(), f))
+                }
+            }
+        )+
+    };
+}
+
+forward_fmt!(Point => Binary, Octal, LowerHex, UpperHex, LowerExp);
+forward_fmt!(MinMax => Binary, Octal, LowerHex, UpperHex, LowerExp);
+forward_fmt!(Color => Binary, Octal, LowerHex, UpperHex, LowerExp);
+forward_fmt!(Point2D => LowerExp);
+forward_fmt_bits!(Point2D => Binary, Octal, LowerHex, UpperHex);
+forward_fmt!(Complex => LowerExp);
+forward_fmt_bits!(Complex => Binary, Octal, LowerHex, UpperHex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_is_unchanged_without_flags() {
+        assert_eq!(Point { x: 1, y: 2 }.to_string(), "(1, 2)");
+        assert_eq!(MinMax(-3, 3).to_string(), "(-3, 3)");
+        assert_eq!(Point2D { x: 3.3, y: 7.2 }.to_string(), "x: 3.3, y: 7.2");
+        let complex = Complex { re: 3.3, im: 7.2 };
+        assert_eq!(complex.to_string(), "3.3 + 7.2i");
+        let color = Color {
+            red: 128,
+            green: 255,
+            blue: 90,
+        };
+        assert_eq!(color.to_string(), "RGB (128, 255, 90) 0x80ff5a");
+    }
+
+    #[test]
+    fn test_display_flags_apply_to_every_field() {
+        let point = Point { x: 1, y: -2 };
+        assert_eq!(format!("{:>4}", point), "(   1,   -2)");
+        assert_eq!(format!("{:*<3}", point), "(1**, -2*)");
+        assert_eq!(format!("{:+}", point), "(+1, -2)");
+        assert_eq!(
+            format!("{:.2}", Point2D { x: 3.3, y: 7.2 }),
+            "x: 3.30, y: 7.20"
+        );
+        let complex = Complex { re: 1.5, im: -0.25 };
+        assert_eq!(format!("{:+.1}", complex), "+1.5 + -0.2i");
+        assert_eq!(format!("{:^5}", MinMax(0, 14)), "(  0  ,  14  )");
+    }
+
+    #[test]
+    fn test_radix_traits() {
+        let point = Point { x: 5, y: 10 };
+        assert_eq!(format!("{:b}", point), "(101, 1010)");
+        assert_eq!(format!("{:#06b}", point), "(0b0101, 0b1010)");
+        assert_eq!(format!("{:o}", point), "(5, 12)");
+        assert_eq!(format!("{:x}", MinMax(255, 4096)), "(ff, 1000)");
+        assert_eq!(format!("{:#X}", MinMax(255, 4096)), "(0xFF, 0x1000)");
+
+        let color = Color {
+            red: 128,
+            green: 3,
+            blue: 90,
+        };
+        assert_eq!(format!("{:02x}", color), "RGB (80, 03, 5a) 0x80035a");
+        assert_eq!(
+            format!("{:08b}", color),
+            "RGB (10000000, 00000011, 01011010) 0x80035a"
+        );
+    }
+
+    #[test]
+    fn test_float_radix_traits_show_bits() {
+        let point = Point2D { x: 1.0, y: -2.0 };
+        assert_eq!(
+            format!("{:x}", point),
+            "x: 3ff0000000000000, y: c000000000000000"
+        );
+        assert_eq!(
+            format!("{:b}", point),
+            format!("x: {:b}, y: {:b}", 1.0f64.to_bits(), (-2.0f64).to_bits())
+        );
+    }
+
+    #[test]
+    fn test_lower_exp() {
+        assert_eq!(format!("{:e}", Point { x: 1200, y: 5 }), "(1.2e3, 5e0)");
+        let complex = Complex {
+            re: 1234.5,
+            im: 0.001,
+        };
+        assert_eq!(format!("{:.2e}", complex), "1.23e3 + 1.00e-3i");
+        assert_eq!(format!("{:e}", MinMax(-100, 100)), "(-1e2, 1e2)");
+    }
+}
@@ -0,0 +1,260 @@
+// geodesy on `City`: great circle distances, bearings and midpoints.
+// latitudes and longitudes are stored in degrees as f32, all the math is done in f64 radians.
+
+use std::error::Error;
+use std::fmt;
+
+use super::City;
+
+// mean earth radius used by the spherical formulas
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+// WGS-84 ellipsoid used by Vincenty's formula
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+#[derive(Debug, PartialEq)]
+pub enum CoordinateError {
+    // |lat| > 90 or not a number
+    Latitude(f32),
+    // |lon| > 180 or not a number
+    Longitude(f32),
+}
+
+impl fmt::Display for CoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoordinateError::Latitude(lat) => write!(f, "latitude {} is outside -90..=90", lat),
+            CoordinateError::Longitude(lon) => {
+                write!(f, "longitude {} is outside -180..=180", lon)
+            }
+        }
+    }
+}
+
+impl Error for CoordinateError {}
+
+impl City {
+    // the validating constructor, NaN fails both range checks
+    pub fn new(name: &str, lat: f32, lon: f32) -> Result<City, CoordinateError> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(CoordinateError::Latitude(lat));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(CoordinateError::Longitude(lon));
+        }
+        Ok(City {
+            name: name.to_owned(),
+            lat,
+            lon,
+        })
+    }
+
+    fn radians(&self) -> (f64, f64) {
+        (
+            f64::from(self.lat).to_radians(),
+            f64::from(self.lon).to_radians(),
+        )
+    }
+
+    // great circle distance on a sphere, in kilometres
+    pub fn haversine_km(&self, other: &City) -> f64 {
+        let (lat1, lon1) = self.radians();
+        let (lat2, lon2) = other.radians();
+
+        let a = ((lat2 - lat1) / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * ((lon2 - lon1) / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+    }
+
+    // distance on the WGS-84 ellipsoid, in kilometres.
+    // the iteration does not converge for nearly antipodal points, that gives `None`
+    pub fn vincenty_km(&self, other: &City) -> Option<f64> {
+        let b = (1.0 - WGS84_F) * WGS84_A;
+        let (lat1, lon1) = self.radians();
+        let (lat2, lon2) = other.radians();
+
+        // reduced latitudes
+        let u1 = ((1.0 - WGS84_F) * lat1.tan()).atan();
+        let u2 = ((1.0 - WGS84_F) * lat2.tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let l = lon2 - lon1;
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                // coincident points
+                return Some(0.0);
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos_sq_alpha = 1.0 - sin_alpha * sin_alpha;
+            // both points on the equator
+            let cos_2sigma_m = if cos_sq_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos_sq_alpha
+            };
+            let c = WGS84_F / 16.0 * cos_sq_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos_sq_alpha));
+
+            let previous = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * WGS84_F
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u_sq = cos_sq_alpha * (WGS84_A * WGS84_A - b * b) / (b * b);
+                let big_a = 1.0
+                    + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+                let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                                    * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+                return Some(b * big_a * (sigma - delta_sigma) / 1000.0);
+            }
+        }
+        None
+    }
+
+    // initial bearing in degrees clockwise from north, in 0..360
+    pub fn bearing_to(&self, other: &City) -> f64 {
+        let (lat1, lon1) = self.radians();
+        let (lat2, lon2) = other.radians();
+        let delta = lon2 - lon1;
+
+        let y = delta.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * delta.cos();
+        (y.atan2(x).to_degrees() + 360.0) % 360.0
+    }
+
+    // the point halfway along the great circle, as (lat, lon) in degrees
+    pub fn midpoint(&self, other: &City) -> (f32, f32) {
+        let (lat1, lon1) = self.radians();
+        let (lat2, lon2) = other.radians();
+        let delta = lon2 - lon1;
+
+        let bx = lat2.cos() * delta.cos();
+        let by = lat2.cos() * delta.sin();
+        let lat = (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by * by).sqrt());
+        let lon = lon1 + by.atan2(lat1.cos() + bx);
+
+        // normalise the longitude back into -180..=180
+        let lon = (lon.to_degrees() + 540.0) % 360.0 - 180.0;
+        (lat.to_degrees() as f32, lon as f32)
+    }
+
+    // the closest other city by haversine distance, entries equal to `self` are skipped
+    pub fn nearest<'a>(&self, cities: &'a [City]) -> Option<&'a City> {
+        cities
+            .iter()
+            .filter(|city| *city != self)
+            .map(|city| (self.haversine_km(city), city))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, city)| city)
+    }
+}
+
+// write a coordinate as degrees, minutes and whole seconds, e.g. 53°20'52"N
+pub fn write_dms(f: &mut fmt::Formatter<'_>, degrees: f32, hemisphere: char) -> fmt::Result {
+    let seconds = (f64::from(degrees).abs() * 3600.0).round() as u64;
+    write!(
+        f,
+        "{}°{:02}'{:02}\"{}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        hemisphere
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn city(name: &str, lat: f32, lon: f32) -> City {
+        City::new(name, lat, lon).unwrap()
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_coordinates() {
+        assert!(City::new("North Pole", 90.0, 0.0).is_ok());
+        assert_eq!(
+            City::new("Nowhere", 90.5, 0.0),
+            Err(CoordinateError::Latitude(90.5))
+        );
+        assert_eq!(
+            City::new("Nowhere", -12.0, -180.5),
+            Err(CoordinateError::Longitude(-180.5))
+        );
+        assert!(City::new("Nowhere", f32::NAN, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_distances() {
+        let london = city("London", 51.5074, -0.1278);
+        let paris = city("Paris", 48.8566, 2.3522);
+        assert!((london.haversine_km(&paris) - 343.5).abs() < 1.0);
+        assert!((london.vincenty_km(&paris).unwrap() - 343.9).abs() < 1.0);
+        assert_eq!(london.haversine_km(&london), 0.0);
+        assert_eq!(london.vincenty_km(&london), Some(0.0));
+
+        // the classic test case from Vincenty's paper: 54972.271 m
+        let flinders_peak = city("Flinders Peak", -37.951_033, 144.424_87);
+        let buninyong = city("Buninyong", -37.652_82, 143.926_5);
+        let distance = flinders_peak.vincenty_km(&buninyong).unwrap();
+        assert!((distance - 54.972_271).abs() < 0.005, "{}", distance);
+    }
+
+    #[test]
+    fn test_bearing_and_midpoint() {
+        let origin = city("Origin", 0.0, 0.0);
+        let east = city("East", 0.0, 90.0);
+        let north = city("North", 10.0, 0.0);
+        assert!((origin.bearing_to(&east) - 90.0).abs() < 1e-9);
+        assert!(origin.bearing_to(&north).abs() < 1e-9);
+        assert!((east.bearing_to(&origin) - 270.0).abs() < 1e-9);
+
+        let (lat, lon) = origin.midpoint(&east);
+        assert!(lat.abs() < 1e-5 && (lon - 45.0).abs() < 1e-5);
+
+        // crossing the antimeridian stays in range
+        let (_, lon) = city("A", 0.0, 170.0).midpoint(&city("B", 0.0, -170.0));
+        assert!((lon.abs() - 180.0).abs() < 1e-4, "{}", lon);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let cities = [
+            city("Dublin", 53.347_78, -6.259_722),
+            city("Oslo", 59.95, 10.75),
+            city("Vancouver", 49.25, -123.1),
+        ];
+        assert_eq!(cities[0].nearest(&cities).unwrap().name, "Oslo");
+        assert_eq!(cities[2].nearest(&cities).unwrap().name, "Dublin");
+        assert_eq!(cities[0].nearest(&cities[..1]), None);
+    }
+
+    #[test]
+    fn test_alternate_display_is_dms() {
+        let dublin = city("Dublin", 53.347_78, -6.259_722);
+        assert_eq!(format!("{:#}", dublin), "Dublin: 53°20'52\"N 6°15'35\"W");
+        assert_eq!(format!("{}", dublin), "Dublin: 53.348°N 6.260°W");
+    }
+}
@@ -2,6 +2,17 @@ use std::error::Error;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
+// the color space conversions live next to flow_of_control's `Color` enum
+#[path = "../../flow_of_control/src/color.rs"]
+mod color;
+// the generic complex number shared with std_expand_types, where it is also passed to libm
+#[path = "../../std_expand_types/src/complex.rs"]
+mod complex;
+mod format;
+mod geo;
+mod parse;
+mod table;
+
 #[derive(Debug)]
 struct Structure(i32);
 
@@ -14,14 +25,16 @@ struct Person<'a> {
     age: u8,
 }
 
+#[derive(Debug, PartialEq)]
 struct Point {
     x: i32,
     y: i32,
 }
 
+// the formatter is passed on to each field, so `{:>4}` or `{:+}` apply to both coordinates
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+        self.fmt_fields(f, Display::fmt)
     }
 }
 
@@ -33,16 +46,16 @@ impl fmt::Display for DisplayStructure {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct MinMax(i64, i64);
 
 impl fmt::Display for MinMax {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.0, self.1)
+        self.fmt_fields(f, Display::fmt)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct Point2D {
     x: f64,
     y: f64,
@@ -50,42 +63,31 @@ struct Point2D {
 
 impl fmt::Display for Point2D {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "x: {}, y: {}", self.x, self.y)
+        self.fmt_fields(f, Display::fmt)
     }
 }
 
-#[derive(Debug)]
-struct Complex {
-    real: f64,
-    imag: f64,
-}
+type Complex = complex::Complex<f64>;
 
 impl fmt::Display for Complex {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{} + {}i", self.real, self.imag)
+        self.fmt_fields(f, Display::fmt)
     }
 }
 
-struct List(Vec<i32>);
+#[derive(Debug, PartialEq)]
+struct List<T>(Vec<T>);
 
-impl fmt::Display for List {
+// `List::styled` in table.rs changes the brackets, separator and indices
+impl<T: Display> fmt::Display for List<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let vec = &self.0;
-        write!(f, "[")?;
-
-        for (count, v) in vec.iter().enumerate() {
-            if count != 0 {
-                write!(f, ", ")?;
-            }
-            write!(f, "{}: {}", count, v)?;
-        }
-
-        write!(f, "]")
+        self.styled().fmt(f)
     }
 }
 
+#[derive(Debug, PartialEq)]
 struct City {
-    name: &'static str,
+    name: String,
     // Latitude
     lat: f32,
     // Longitude
@@ -97,6 +99,14 @@ impl Display for City {
         let lat_c = if self.lat >= 0.0 { 'N' } else { 'S' };
         let lon_c = if self.lon >= 0.0 { 'E' } else { 'W' };
 
+        // `{:#}` writes degrees, minutes and seconds instead
+        if f.alternate() {
+            write!(f, "{}: ", self.name)?;
+            geo::write_dms(f, self.lat, lat_c)?;
+            write!(f, " ")?;
+            return geo::write_dms(f, self.lon, lon_c);
+        }
+
         write!(
             f,
             "{}: {:.3}°{} {:.3}°{}",
@@ -109,7 +119,7 @@ impl Display for City {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 struct Color {
     red: u8,
     green: u8,
@@ -118,10 +128,16 @@ struct Color {
 
 impl Display for Color {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "RGB ({}, {}, {}) 0x{:02x}{:02x}{:02x}",
-            self.red, self.green, self.blue, self.red, self.green, self.blue,
+        self.fmt_fields(f, Display::fmt)
+    }
+}
+
+impl From<&Color> for color::Color {
+    fn from(color: &Color) -> Self {
+        color::Color::RGB(
+            u32::from(color.red),
+            u32::from(color.green),
+            u32::from(color.blue),
         )
     }
 }
@@ -212,38 +228,56 @@ fn main() {
     println!("Display: {}", point);
     println!("Debug: {:?}", point);
     println!("Debug: {key:?}", key = point);
-    // println!("What does Point2d look like in binary: {:b}?", point);
+    println!("What does Point2d look like in binary: {:b}?", point);
+    println!("Aligned: [{:>8.2}] [{:<8.2}]", point, point);
 
-    let complex = Complex {
-        real: 3.3,
-        imag: 7.2,
-    };
+    let complex = Complex { re: 3.3, im: 7.2 };
     println!("Display: {}", complex);
+    println!("LowerExp: {:e}", complex);
+    println!("Squared: {}", complex * complex);
+    println!("Polar: {:?}", complex.to_polar());
+    // `Debug` comes from complex.rs, written the way C prints it: 3.3+7.2i
     println!("Debug: {:?}", complex);
 
     let v = List(vec![1, 2, 3]);
     println!("{}", v);
+    println!("{}", v.styled().indexed(false).separator(" | "));
+    println!("{}", List(vec!["a", "b"]).styled().brackets("{", "}"));
+    print!(
+        "{}",
+        table::Table::from_rows("value", &v.0).border(table::Border::None)
+    );
 
-    for city in [
-        City {
-            name: "Dublin",
-            lat: 53.347778,
-            lon: -6.259722,
-        },
-        City {
-            name: "Oslo",
-            lat: 59.95,
-            lon: 10.75,
-        },
-        City {
-            name: "Vancouver",
-            lat: 49.25,
-            lon: -123.1,
-        },
-    ]
-    .iter()
-    {
+    let cities = [
+        City::new("Dublin", 53.347778, -6.259722).unwrap(),
+        City::new("Oslo", 59.95, 10.75).unwrap(),
+        City::new("Vancouver", 49.25, -123.1).unwrap(),
+    ];
+    for city in cities.iter() {
         println!("{}", city);
+        println!("{:#}", city);
+        if let Some(nearest) = city.nearest(&cities) {
+            println!(
+                "nearest is {}, {:.1} km away, bearing {:.1}°",
+                nearest.name,
+                city.haversine_km(nearest),
+                city.bearing_to(nearest)
+            );
+        }
+    }
+    println!(
+        "{}",
+        table::Table::from_rows("City", &cities)
+            .border(table::Border::Unicode)
+            .max_width(table::Table::terminal_width())
+    );
+    println!(
+        "Dublin to Oslo on the ellipsoid: {:.1} km, midpoint {:?}",
+        cities[0].vincenty_km(&cities[1]).unwrap_or(f64::NAN),
+        cities[0].midpoint(&cities[1])
+    );
+    if let Err(err) = City::new("Nowhere", 91.0, 0.0) {
+        println!("{}", err);
     }
 
     let color_iter = [
@@ -265,10 +299,29 @@ fn main() {
     ]
     .iter();
 
+    // tables size their columns to the widest row
+    println!(
+        "{}",
+        table::Table::from_rows("Color", color_iter.as_slice())
+    );
+
     for color in color_iter.clone() {
         println!("{:?}", *color);
     }
     for color in color_iter {
         println!("{}", color);
+        println!("{:3}", color);
+        println!("{:02X}", color);
+        println!("{}", color::Color::from(color).to_hsl());
+    }
+
+    // every `Display` above can be parsed back
+    println!("{:?}", "(1, 2)".parse::<Point>());
+    println!("{:?}", "3.3 + 7.2i".parse::<Complex>());
+    println!("{:?}", "[0: 1, 1: 2]".parse::<List<i32>>());
+    println!("{:?}", "Dublin: 53.348°N 6.260°W".parse::<City>());
+    println!("{:?}", "RGB (128, 255, 90) 0x80ff5a".parse::<Color>());
+    if let Err(err) = "RGB (128, 255, 90) 0x80ff5b".parse::<Color>() {
+        println!("{}", err);
     }
 }
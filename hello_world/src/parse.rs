@@ -0,0 +1,372 @@
+// `FromStr` for every `Display` type in main, each accepts exactly what its `Display` writes
+// so that `s.parse::<T>()` undoes `t.to_string()`.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::geo::CoordinateError;
+use super::{City, Color, Complex, List, MinMax, Point, Point2D};
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    // the text does not have the shape `Display` writes, e.g. missing parentheses
+    Format { expected: &'static str },
+    // the shape is right but one field does not parse
+    Field { field: &'static str, value: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Format { expected } => write!(f, "expected `{}`", expected),
+            ParseError::Field { field, value } => {
+                write!(f, "invalid value for `{}`: {:?}", field, value)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+fn field<T: FromStr>(field: &'static str, value: &str) -> Result<T, ParseError> {
+    value.parse().map_err(|_| ParseError::Field {
+        field,
+        value: value.to_owned(),
+    })
+}
+
+// strip `prefix` and `suffix` off `s`, failing with `expected` as the shape
+fn between<'a>(
+    s: &'a str,
+    prefix: &str,
+    suffix: &str,
+    expected: &'static str,
+) -> Result<&'a str, ParseError> {
+    s.strip_prefix(prefix)
+        .and_then(|s| s.strip_suffix(suffix))
+        .ok_or(ParseError::Format { expected })
+}
+
+fn split_once<'a>(
+    s: &'a str,
+    separator: &str,
+    expected: &'static str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    s.split_once(separator)
+        .ok_or(ParseError::Format { expected })
+}
+
+impl FromStr for Point {
+    type Err = ParseError;
+
+    // (1, 2)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "(x, y)";
+        let (x, y) = split_once(between(s, "(", ")", EXPECTED)?, ", ", EXPECTED)?;
+        Ok(Point {
+            x: field("x", x)?,
+            y: field("y", y)?,
+        })
+    }
+}
+
+impl FromStr for MinMax {
+    type Err = ParseError;
+
+    // (-3, 3)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "(min, max)";
+        let (min, max) = split_once(between(s, "(", ")", EXPECTED)?, ", ", EXPECTED)?;
+        Ok(MinMax(field("min", min)?, field("max", max)?))
+    }
+}
+
+impl FromStr for Point2D {
+    type Err = ParseError;
+
+    // x: 3.3, y: 7.2
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "x: <x>, y: <y>";
+        let (x, y) = split_once(between(s, "x: ", "", EXPECTED)?, ", y: ", EXPECTED)?;
+        Ok(Point2D {
+            x: field("x", x)?,
+            y: field("y", y)?,
+        })
+    }
+}
+
+impl FromStr for Complex {
+    type Err = ParseError;
+
+    // 3.3 + 7.2i, a negative imaginary part is written as `3.3 + -7.2i`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "<re> + <im>i";
+        let (re, im) = split_once(between(s, "", "i", EXPECTED)?, " + ", EXPECTED)?;
+        Ok(Complex {
+            re: field("re", re)?,
+            im: field("im", im)?,
+        })
+    }
+}
+
+impl<T: FromStr> FromStr for List<T> {
+    type Err = ParseError;
+
+    // [0: 1, 1: 2], the indices have to count up from zero
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "[0: <v>, 1: <v>, ...]";
+        let body = between(s, "[", "]", EXPECTED)?;
+        if body.is_empty() {
+            return Ok(List(Vec::new()));
+        }
+
+        let mut vec = Vec::new();
+        for (count, entry) in body.split(", ").enumerate() {
+            let (index, value) = split_once(entry, ": ", EXPECTED)?;
+            if field::<usize>("index", index)? != count {
+                return Err(ParseError::Field {
+                    field: "index",
+                    value: index.to_owned(),
+                });
+            }
+            vec.push(field("value", value)?);
+        }
+        Ok(List(vec))
+    }
+}
+
+// one `53.348°N` coordinate, `positive` and `negative` are the allowed hemisphere letters
+fn coordinate(
+    name: &'static str,
+    s: &str,
+    positive: char,
+    negative: char,
+) -> Result<f32, ParseError> {
+    let invalid = || ParseError::Field {
+        field: name,
+        value: s.to_owned(),
+    };
+    let hemisphere = s.chars().last().ok_or_else(invalid)?;
+    let degrees = s[..s.len() - hemisphere.len_utf8()]
+        .strip_suffix('°')
+        .ok_or_else(invalid)?;
+    let degrees: f32 = degrees.parse().map_err(|_| invalid())?;
+    if degrees < 0.0 {
+        return Err(invalid());
+    }
+
+    if hemisphere == positive {
+        Ok(degrees)
+    } else if hemisphere == negative {
+        Ok(-degrees)
+    } else {
+        Err(invalid())
+    }
+}
+
+impl FromStr for City {
+    type Err = ParseError;
+
+    // Dublin: 53.348°N 6.260°W
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "<name>: <lat>°N|S <lon>°E|W";
+        let (name, coordinates) = s
+            .rsplit_once(": ")
+            .ok_or(ParseError::Format { expected: EXPECTED })?;
+        let (lat_text, lon_text) = split_once(coordinates, " ", EXPECTED)?;
+        let lat = coordinate("lat", lat_text, 'N', 'S')?;
+        let lon = coordinate("lon", lon_text, 'E', 'W')?;
+        // `City::new` does the range checks, the error names the text that was out of range
+        City::new(name, lat, lon).map_err(|err| match err {
+            CoordinateError::Latitude(_) => ParseError::Field {
+                field: "lat",
+                value: lat_text.to_owned(),
+            },
+            CoordinateError::Longitude(_) => ParseError::Field {
+                field: "lon",
+                value: lon_text.to_owned(),
+            },
+        })
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseError;
+
+    // RGB (128, 255, 90) 0x80ff5a, the hex code has to agree with the decimal channels
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "RGB (<red>, <green>, <blue>) 0x<hex>";
+        let (channels, hex) = split_once(between(s, "RGB (", "", EXPECTED)?, ") 0x", EXPECTED)?;
+        let (red, rest) = split_once(channels, ", ", EXPECTED)?;
+        let (green, blue) = split_once(rest, ", ", EXPECTED)?;
+        let color = Color {
+            red: field("red", red)?,
+            green: field("green", green)?,
+            blue: field("blue", blue)?,
+        };
+
+        let expected_hex = format!("{:02x}{:02x}{:02x}", color.red, color.green, color.blue);
+        if hex != expected_hex {
+            return Err(ParseError::Field {
+                field: "hex",
+                value: hex.to_owned(),
+            });
+        }
+        Ok(color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_examples() {
+        assert_eq!("(1, 2)".parse(), Ok(Point { x: 1, y: 2 }));
+        assert_eq!("(-300, 300)".parse(), Ok(MinMax(-300, 300)));
+        assert_eq!("x: 3.3, y: 7.2".parse(), Ok(Point2D { x: 3.3, y: 7.2 }));
+        assert_eq!("3.3 + 7.2i".parse(), Ok(Complex { re: 3.3, im: 7.2 }));
+        assert_eq!("[0: 1, 1: 2]".parse(), Ok(List(vec![1, 2])));
+        assert_eq!("[]".parse(), Ok(List::<i32>(vec![])));
+        assert_eq!(
+            "Dublin: 53.348°N 6.260°W".parse(),
+            Ok(City {
+                name: "Dublin".to_owned(),
+                lat: 53.348,
+                lon: -6.26,
+            })
+        );
+        assert_eq!(
+            "RGB (128, 255, 90) 0x80ff5a".parse(),
+            Ok(Color {
+                red: 128,
+                green: 255,
+                blue: 90
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_field() {
+        let invalid = |field, value: &str| ParseError::Field {
+            field,
+            value: value.to_owned(),
+        };
+        assert_eq!("(1, a)".parse::<Point>().unwrap_err(), invalid("y", "a"));
+        assert_eq!(
+            "(1 2)".parse::<Point>().unwrap_err(),
+            ParseError::Format { expected: "(x, y)" }
+        );
+        assert_eq!(
+            "(1, 2.5)".parse::<MinMax>().unwrap_err(),
+            invalid("max", "2.5")
+        );
+        assert_eq!(
+            "x: ?, y: 1".parse::<Point2D>().unwrap_err(),
+            invalid("x", "?")
+        );
+        assert_eq!("1 + bi".parse::<Complex>().unwrap_err(), invalid("im", "b"));
+        assert_eq!(
+            "[0: 1, 2: 2]".parse::<List<i32>>().unwrap_err(),
+            invalid("index", "2")
+        );
+        assert_eq!(
+            "Oslo: 59.950°N 10.750°S".parse::<City>().unwrap_err(),
+            invalid("lon", "10.750°S")
+        );
+        assert_eq!(
+            "RGB (256, 0, 0) 0x000000".parse::<Color>().unwrap_err(),
+            invalid("red", "256")
+        );
+        assert_eq!(
+            "RGB (1, 2, 3) 0x010204".parse::<Color>().unwrap_err(),
+            invalid("hex", "010204")
+        );
+    }
+
+    #[test]
+    fn test_parse_city_checks_ranges() {
+        let invalid = |field, value: &str| ParseError::Field {
+            field,
+            value: value.to_owned(),
+        };
+        assert_eq!(
+            "X: 95.000°N 0.000°E".parse::<City>().unwrap_err(),
+            invalid("lat", "95.000°N")
+        );
+        assert_eq!(
+            "X: 0.000°S 180.500°W".parse::<City>().unwrap_err(),
+            invalid("lon", "180.500°W")
+        );
+        assert!("X: 90.000°S 180.000°E".parse::<City>().is_ok());
+    }
+
+    // a small xorshift generator, enough to drive a property test without extra dependencies
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn range(&mut self, low: i64, high: i64) -> i64 {
+            low + (self.next() % (high - low + 1) as u64) as i64
+        }
+
+        fn float(&mut self) -> f64 {
+            f64::from_bits(self.next() >> 2)
+                * if self.next().is_multiple_of(2) {
+                    1.0
+                } else {
+                    -1.0
+                }
+        }
+    }
+
+    fn assert_round_trip<T>(value: T)
+    where
+        T: fmt::Display + FromStr<Err = ParseError> + PartialEq + fmt::Debug,
+    {
+        let text = value.to_string();
+        assert_eq!(text.parse::<T>(), Ok(value), "{}", text);
+    }
+
+    #[test]
+    fn test_display_then_parse_is_identity() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..1000 {
+            assert_round_trip(Point {
+                x: rng.next() as i32,
+                y: rng.next() as i32,
+            });
+            assert_round_trip(MinMax(rng.next() as i64, rng.next() as i64));
+            assert_round_trip(Point2D {
+                x: rng.float(),
+                y: rng.float(),
+            });
+            assert_round_trip(Complex {
+                re: rng.float(),
+                im: rng.float(),
+            });
+
+            let len = rng.range(0, 10) as usize;
+            assert_round_trip(List((0..len).map(|_| rng.next() as i32).collect()));
+
+            // `Display` keeps three decimals, so only generate coordinates it can represent
+            assert_round_trip(City {
+                name: format!("City {}", rng.next()),
+                lat: rng.range(-90_000, 90_000) as f32 / 1000.0,
+                lon: rng.range(-180_000, 180_000) as f32 / 1000.0,
+            });
+            assert_round_trip(Color {
+                red: rng.next() as u8,
+                green: rng.next() as u8,
+                blue: rng.next() as u8,
+            });
+        }
+    }
+}
@@ -0,0 +1,367 @@
+// pretty printing for collections: a configurable `List` style and a `Table` that sizes its
+// columns, wraps cells to a maximum width and draws ASCII or Unicode borders.
+// widths are counted in chars, which is right for everything main prints (`°` included).
+
+use std::env;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use super::List;
+
+// `List` rendered with custom brackets, separator and optional `index: ` prefixes
+pub struct ListStyle<'a, T> {
+    list: &'a List<T>,
+    open: &'a str,
+    close: &'a str,
+    separator: &'a str,
+    indexed: bool,
+}
+
+impl<T> List<T> {
+    // the default style is what `Display` writes: `[0: 1, 1: 2]`
+    pub fn styled(&self) -> ListStyle<'_, T> {
+        ListStyle {
+            list: self,
+            open: "[",
+            close: "]",
+            separator: ", ",
+            indexed: true,
+        }
+    }
+}
+
+impl<'a, T> ListStyle<'a, T> {
+    pub fn brackets(mut self, open: &'a str, close: &'a str) -> Self {
+        self.open = open;
+        self.close = close;
+        self
+    }
+
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn indexed(mut self, indexed: bool) -> Self {
+        self.indexed = indexed;
+        self
+    }
+}
+
+impl<'a, T: Display> Display for ListStyle<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.open)?;
+        for (count, v) in self.list.0.iter().enumerate() {
+            if count != 0 {
+                f.write_str(self.separator)?;
+            }
+            if self.indexed {
+                write!(f, "{}: ", count)?;
+            }
+            // pass the formatter on so `{:>3}` pads every element
+            v.fmt(f)?;
+        }
+        f.write_str(self.close)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Border {
+    // columns separated by two spaces, nothing drawn
+    None,
+    Ascii,
+    Unicode,
+}
+
+// the pieces of a border: horizontal, vertical, then the corners and joints of the
+// top, middle and bottom lines, each as (left, middle, right)
+struct BoxChars {
+    horizontal: char,
+    vertical: char,
+    top: (char, char, char),
+    middle: (char, char, char),
+    bottom: (char, char, char),
+}
+
+const ASCII: BoxChars = BoxChars {
+    horizontal: '-',
+    vertical: '|',
+    top: ('+', '+', '+'),
+    middle: ('+', '+', '+'),
+    bottom: ('+', '+', '+'),
+};
+
+const UNICODE: BoxChars = BoxChars {
+    horizontal: '─',
+    vertical: '│',
+    top: ('┌', '┬', '┐'),
+    middle: ('├', '┼', '┤'),
+    bottom: ('└', '┴', '┘'),
+};
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    border: Border,
+    max_width: Option<usize>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+            border: Border::Ascii,
+            max_width: None,
+        }
+    }
+
+    // one row per element: an index column and the element's `Display`
+    pub fn from_rows<T: Display>(header: &str, rows: &[T]) -> Table {
+        let mut table = Table::new(&["#", header]);
+        for (i, row) in rows.iter().enumerate() {
+            table.push_row(vec![i.to_string(), row.to_string()]);
+        }
+        table
+    }
+
+    // missing cells are left empty, extra cells are dropped
+    pub fn push_row(&mut self, mut cells: Vec<String>) {
+        cells.resize(self.headers.len(), String::new());
+        self.rows.push(cells);
+    }
+
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    // the total width of a rendered line, cells are wrapped to stay within it
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    // the width of the terminal from `$COLUMNS`, 80 when it is unset
+    pub fn terminal_width() -> usize {
+        env::var("COLUMNS")
+            .ok()
+            .and_then(|columns| columns.parse().ok())
+            .unwrap_or(80)
+    }
+
+    // the chars a border adds around `columns` columns
+    fn overhead(&self, columns: usize) -> usize {
+        match self.border {
+            Border::None => 2 * columns.saturating_sub(1),
+            Border::Ascii | Border::Unicode => 3 * columns + 1,
+        }
+    }
+
+    pub fn column_widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in self.rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        // shrink the widest column until the table fits, never below one char
+        if let Some(max_width) = self.max_width {
+            let overhead = self.overhead(widths.len());
+            while widths.iter().sum::<usize>() + overhead > max_width {
+                match widths.iter_mut().filter(|w| **w > 1).max() {
+                    Some(widest) => *widest -= 1,
+                    None => break,
+                }
+            }
+        }
+        widths
+    }
+
+    fn write_rule(
+        &self,
+        f: &mut Formatter<'_>,
+        widths: &[usize],
+        chars: &BoxChars,
+        (left, middle, right): (char, char, char),
+    ) -> fmt::Result {
+        write!(f, "{}", left)?;
+        for (i, width) in widths.iter().enumerate() {
+            if i != 0 {
+                write!(f, "{}", middle)?;
+            }
+            for _ in 0..width + 2 {
+                write!(f, "{}", chars.horizontal)?;
+            }
+        }
+        writeln!(f, "{}", right)
+    }
+
+    fn write_row(&self, f: &mut Formatter<'_>, widths: &[usize], row: &[String]) -> fmt::Result {
+        let wrapped: Vec<Vec<String>> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, &width)| wrap(cell, width))
+            .collect();
+        let height = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        for line in 0..height {
+            for (i, (cell, &width)) in wrapped.iter().zip(widths).enumerate() {
+                let text = cell.get(line).map(String::as_str).unwrap_or("");
+                match self.border {
+                    // no padding after the last column, so lines carry no trailing spaces
+                    Border::None if i + 1 == widths.len() && i == 0 => write!(f, "{}", text)?,
+                    Border::None if i + 1 == widths.len() => write!(f, "  {}", text)?,
+                    Border::None if i == 0 => write!(f, "{:<1$}", text, width)?,
+                    Border::None => write!(f, "  {:<1$}", text, width)?,
+                    Border::Ascii => write!(f, "{} {:<2$} ", ASCII.vertical, text, width)?,
+                    Border::Unicode => write!(f, "{} {:<2$} ", UNICODE.vertical, text, width)?,
+                }
+            }
+            match self.border {
+                Border::None => writeln!(f)?,
+                Border::Ascii => writeln!(f, "{}", ASCII.vertical)?,
+                Border::Unicode => writeln!(f, "{}", UNICODE.vertical)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// break `text` into lines of at most `width` chars, on spaces where possible
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let mut word: Vec<char> = word.chars().collect();
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(word.iter());
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(line);
+            line = String::new();
+        }
+        // a word longer than the column is split hard
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line.extend(word.iter());
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let widths = self.column_widths();
+        let chars = match self.border {
+            Border::None => None,
+            Border::Ascii => Some(&ASCII),
+            Border::Unicode => Some(&UNICODE),
+        };
+
+        if let Some(chars) = chars {
+            self.write_rule(f, &widths, chars, chars.top)?;
+        }
+        self.write_row(f, &widths, &self.headers)?;
+        if let Some(chars) = chars {
+            self.write_rule(f, &widths, chars, chars.middle)?;
+        }
+        for row in self.rows.iter() {
+            self.write_row(f, &widths, row)?;
+        }
+        if let Some(chars) = chars {
+            self.write_rule(f, &widths, chars, chars.bottom)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_styles() {
+        let list = List(vec!["a", "b", "c"]);
+        assert_eq!(list.to_string(), "[0: a, 1: b, 2: c]");
+        assert_eq!(
+            list.styled().indexed(false).separator(" | ").to_string(),
+            "[a | b | c]"
+        );
+        assert_eq!(
+            list.styled().brackets("{", "}").separator("; ").to_string(),
+            "{0: a; 1: b; 2: c}"
+        );
+        assert_eq!(format!("{:>2}", List(vec![1, 22])), "[0:  1, 1: 22]");
+        assert_eq!(List::<i32>(vec![]).to_string(), "[]");
+    }
+
+    #[test]
+    fn test_ascii_table() {
+        let table = Table::from_rows("value", &[1.5, 22.25]);
+        assert_eq!(
+            table.to_string(),
+            "\
++---+-------+
+| # | value |
++---+-------+
+| 0 | 1.5   |
+| 1 | 22.25 |
++---+-------+
+"
+        );
+    }
+
+    #[test]
+    fn test_unicode_and_plain_tables() {
+        let mut table = Table::new(&["name", "lat"]).border(Border::Unicode);
+        table.push_row(vec!["Oslo".to_owned(), "59.95°".to_owned()]);
+        assert_eq!(
+            table.to_string(),
+            "\
+┌──────┬────────┐
+│ name │ lat    │
+├──────┼────────┤
+│ Oslo │ 59.95° │
+└──────┴────────┘
+"
+        );
+
+        let table = Table::new(&["a", "b"]).border(Border::None);
+        assert_eq!(table.to_string(), "a  b\n");
+    }
+
+    #[test]
+    fn test_wrap_to_max_width() {
+        let table = Table::from_rows("text", &["the quick brown fox"]).max_width(17);
+        // `| 0 | ` and ` |` leave nine chars for the text column
+        assert_eq!(table.column_widths(), vec![1, 9]);
+        assert_eq!(
+            table.to_string(),
+            "\
++---+-----------+
+| # | text      |
++---+-----------+
+| 0 | the quick |
+|   | brown fox |
++---+-----------+
+"
+        );
+        for line in table.to_string().lines() {
+            assert!(line.chars().count() <= 17);
+        }
+    }
+
+    #[test]
+    fn test_wrap_splits_long_words() {
+        assert_eq!(wrap("abcdefg hi", 3), vec!["abc", "def", "g", "hi"]);
+        assert_eq!(wrap("", 3), vec![""]);
+    }
+}
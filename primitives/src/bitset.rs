@@ -0,0 +1,446 @@
+// sets of small unsigned integers stored as bits, bit `i % 64` of word `i / 64` is element `i`.
+// `BitSet<N>` has a fixed capacity of `N * 64` and lives on the stack, `BitVec` grows as
+// elements are inserted. the read only operations are shared through the `Bits` trait,
+// which only needs the words.
+
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, BitXor};
+
+const WORD: usize = u64::BITS as usize;
+
+pub trait Bits {
+    fn words(&self) -> &[u64];
+
+    fn test(&self, i: usize) -> bool {
+        self.words()
+            .get(i / WORD)
+            .is_some_and(|word| word & (1 << (i % WORD)) != 0)
+    }
+
+    // the number of elements
+    fn count_ones(&self) -> usize {
+        self.words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    // the number of elements smaller than `i`
+    fn rank(&self, i: usize) -> usize {
+        let words = self.words();
+        let (full, rest) = (i / WORD, i % WORD);
+        let below: usize = words
+            .iter()
+            .take(full)
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        match words.get(full) {
+            Some(word) if rest > 0 => below + (word & ((1 << rest) - 1)).count_ones() as usize,
+            _ => below,
+        }
+    }
+
+    // the `k`th smallest element counting from 0, `None` when there are not that many
+    fn select(&self, k: usize) -> Option<usize> {
+        let mut remaining = k;
+        for (i, &word) in self.words().iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                let mut word = word;
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return Some(i * WORD + word.trailing_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    // the elements in increasing order
+    fn iter(&self) -> Ones<'_> {
+        Ones {
+            words: self.words(),
+            index: 0,
+            current: self.words().first().copied().unwrap_or(0),
+        }
+    }
+}
+
+pub struct Ones<'a> {
+    words: &'a [u64],
+    index: usize,
+    // what is left of `words[index]`
+    current: u64,
+}
+
+impl<'a> Iterator for Ones<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.current &= self.current - 1;
+        Some(self.index * WORD + bit)
+    }
+}
+
+// the set as a binary number, element 0 is the rightmost digit: {0, 1, 4} is `10011`.
+// `{:#b}` adds `0b`, width and `0` padding work like they do for integers
+fn fmt_binary(words: &[u64], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let digits = match words.iter().rposition(|&w| w != 0) {
+        None => "0".to_owned(),
+        Some(top) => {
+            let mut digits = format!("{:b}", words[top]);
+            for word in words[..top].iter().rev() {
+                digits.push_str(&format!("{:064b}", word));
+            }
+            digits
+        }
+    };
+    f.pad_integral(true, "0b", &digits)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct BitSet<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> BitSet<N> {
+    pub const CAPACITY: usize = N * WORD;
+
+    pub fn new() -> Self {
+        BitSet { words: [0; N] }
+    }
+
+    // panics when `i` is not below `CAPACITY`, like indexing an array out of bounds.
+    // returns whether `i` was newly added
+    pub fn set(&mut self, i: usize) -> bool {
+        assert!(i < Self::CAPACITY, "{} does not fit in a BitSet<{}>", i, N);
+        let was_set = self.test(i);
+        self.words[i / WORD] |= 1 << (i % WORD);
+        !was_set
+    }
+
+    // returns whether `i` was there
+    pub fn clear(&mut self, i: usize) -> bool {
+        let was_set = self.test(i);
+        if was_set {
+            self.words[i / WORD] &= !(1 << (i % WORD));
+        }
+        was_set
+    }
+
+    fn zip_with(&self, other: &Self, op: fn(u64, u64) -> u64) -> Self {
+        let mut words = self.words;
+        for (word, &other) in words.iter_mut().zip(other.words.iter()) {
+            *word = op(*word, other);
+        }
+        BitSet { words }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+}
+
+impl<const N: usize> Bits for BitSet<N> {
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+impl<const N: usize> Default for BitSet<N> {
+    fn default() -> Self {
+        BitSet::new()
+    }
+}
+
+// panics on elements that do not fit, see `set`
+impl<const N: usize> FromIterator<usize> for BitSet<N> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitSet::new();
+        for i in iter {
+            set.set(i);
+        }
+        set
+    }
+}
+
+impl<const N: usize> fmt::Debug for BitSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> fmt::Binary for BitSet<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_binary(&self.words, f)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct BitVec {
+    words: Vec<u64>,
+}
+
+impl BitVec {
+    pub fn new() -> Self {
+        BitVec::default()
+    }
+
+    // grows to fit `i`, returns whether `i` was newly added
+    pub fn set(&mut self, i: usize) -> bool {
+        if i / WORD >= self.words.len() {
+            self.words.resize(i / WORD + 1, 0);
+        }
+        let was_set = self.test(i);
+        self.words[i / WORD] |= 1 << (i % WORD);
+        !was_set
+    }
+
+    // returns whether `i` was there, never shrinks
+    pub fn clear(&mut self, i: usize) -> bool {
+        let was_set = self.test(i);
+        if was_set {
+            self.words[i / WORD] &= !(1 << (i % WORD));
+        }
+        was_set
+    }
+
+    // words missing from the shorter side count as zero
+    fn zip_with(&self, other: &Self, op: fn(u64, u64) -> u64) -> Self {
+        let len = self.words.len().max(other.words.len());
+        let word = |words: &[u64], i: usize| words.get(i).copied().unwrap_or(0);
+        BitVec {
+            words: (0..len)
+                .map(|i| op(word(&self.words, i), word(&other.words, i)))
+                .collect(),
+        }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    // without the trailing zero words, so equal sets compare equal however they grew
+    fn significant(&self) -> &[u64] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|&w| w != 0)
+            .map_or(0, |top| top + 1);
+        &self.words[..len]
+    }
+}
+
+impl Bits for BitVec {
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+impl PartialEq for BitVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for BitVec {}
+
+impl FromIterator<usize> for BitVec {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = BitVec::new();
+        for i in iter {
+            set.set(i);
+        }
+        set
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl fmt::Binary for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_binary(&self.words, f)
+    }
+}
+
+// `|`, `&` and `^` on references, like `HashSet`
+macro_rules! impl_set_ops {
+    ($([$($generics:tt)*] $t:ty),*) => {
+        $(
+            impl<$($generics)*> BitOr for &$t {
+                type Output = $t;
+
+                fn bitor(self, rhs: Self) -> $t {
+                    self.union(rhs)
+                }
+            }
+
+            impl<$($generics)*> BitAnd for &$t {
+                type Output = $t;
+
+                fn bitand(self, rhs: Self) -> $t {
+                    self.intersection(rhs)
+                }
+            }
+
+            impl<$($generics)*> BitXor for &$t {
+                type Output = $t;
+
+                fn bitxor(self, rhs: Self) -> $t {
+                    self.symmetric_difference(rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_set_ops!([const N: usize] BitSet<N>, [] BitVec);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // xorshift, enough randomness to cross-check against `HashSet`
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    fn sorted(set: &HashSet<usize>) -> Vec<usize> {
+        let mut v: Vec<usize> = set.iter().copied().collect();
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn test_set_clear_test() {
+        let mut set = BitSet::<2>::new();
+        assert!(set.set(0));
+        assert!(set.set(127));
+        assert!(!set.set(127));
+        assert!(set.test(127) && set.test(0) && !set.test(64));
+        assert!(set.clear(0));
+        assert!(!set.clear(0));
+        assert_eq!(set.count_ones(), 1);
+        // out of range reads are simply absent
+        assert!(!set.test(1000));
+
+        let mut vec = BitVec::new();
+        assert!(vec.set(1000));
+        assert!(vec.test(1000));
+        assert_eq!(vec.words().len(), 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_bitset_capacity() {
+        BitSet::<1>::new().set(64);
+    }
+
+    #[test]
+    fn test_rank_select() {
+        let set: BitVec = vec![1, 5, 64, 65, 200].into_iter().collect();
+        assert_eq!(set.rank(0), 0);
+        assert_eq!(set.rank(2), 1);
+        assert_eq!(set.rank(64), 2);
+        assert_eq!(set.rank(66), 4);
+        assert_eq!(set.rank(10_000), 5);
+        let selected: Vec<Option<usize>> = (0..6).map(|k| set.select(k)).collect();
+        assert_eq!(
+            selected,
+            vec![Some(1), Some(5), Some(64), Some(65), Some(200), None]
+        );
+        for (k, i) in set.iter().enumerate() {
+            assert_eq!(set.rank(i), k);
+            assert_eq!(set.select(k), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_binary_and_debug() {
+        let set: BitSet<1> = vec![0, 1, 4].into_iter().collect();
+        assert_eq!(format!("{:b}", set), "10011");
+        assert_eq!(format!("{:#010b}", set), "0b00010011");
+        assert_eq!(format!("{:?}", set), "{0, 1, 4}");
+        assert_eq!(format!("{:b}", BitVec::new()), "0");
+
+        let wide: BitVec = vec![0, 64].into_iter().collect();
+        assert_eq!(format!("{:b}", wide), format!("1{:064b}", 1));
+    }
+
+    #[test]
+    fn test_bitvec_equality_ignores_capacity() {
+        let mut grown: BitVec = vec![3, 300].into_iter().collect();
+        grown.clear(300);
+        assert_eq!(grown, vec![3].into_iter().collect());
+        assert_eq!(&grown & &BitVec::new(), BitVec::new());
+    }
+
+    #[test]
+    fn test_against_hash_set() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..200 {
+            let a: HashSet<usize> = (0..rng.below(40)).map(|_| rng.below(256)).collect();
+            let b: HashSet<usize> = (0..rng.below(40)).map(|_| rng.below(300)).collect();
+
+            let vec_a: BitVec = a.iter().copied().collect();
+            let vec_b: BitVec = b.iter().copied().collect();
+            let set_a: BitSet<5> = a.iter().copied().collect();
+            let set_b: BitSet<5> = b.iter().copied().collect();
+
+            let union: HashSet<usize> = a.union(&b).copied().collect();
+            let intersection: HashSet<usize> = a.intersection(&b).copied().collect();
+            let xor: HashSet<usize> = a.symmetric_difference(&b).copied().collect();
+
+            for (expected, vec, set) in [
+                (&union, &vec_a | &vec_b, &set_a | &set_b),
+                (&intersection, &vec_a & &vec_b, &set_a & &set_b),
+                (&xor, &vec_a ^ &vec_b, &set_a ^ &set_b),
+            ]
+            .iter()
+            {
+                assert_eq!(vec.iter().collect::<Vec<_>>(), sorted(expected));
+                assert_eq!(set.iter().collect::<Vec<_>>(), sorted(expected));
+                assert_eq!(vec.count_ones(), expected.len());
+                assert_eq!(set.count_ones(), expected.len());
+            }
+            for i in 0..320 {
+                assert_eq!(vec_a.test(i), a.contains(&i));
+                assert_eq!(set_a.test(i), a.contains(&i));
+                assert_eq!(vec_a.rank(i), a.iter().filter(|&&x| x < i).count());
+            }
+        }
+    }
+}
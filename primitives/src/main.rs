@@ -7,31 +7,44 @@
 // arrays [1,2,3]
 // tuples (1, true)
 
-use std::fmt;
-use std::fmt::{Display, Formatter};
 use std::intrinsics::transmute;
 
+use bitset::{BitSet, BitVec, Bits};
+
+mod bitset;
+mod matrix;
+mod stats;
+
 fn reverse(pair: (i32, bool)) -> (bool, i32) {
     let (integer, boolean) = pair;
     (boolean, integer)
 }
 
-#[derive(Debug)]
-struct Matrix(f32, f32, f32, f32);
-
-impl Display for Matrix {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "( {} {} )\n( {} {} )", self.0, self.1, self.2, self.3)
-    }
-}
+// the 2x2 case of the generic matrix
+type Matrix = matrix::Matrix<f32, 2, 2>;
 
 fn transpose(matrix: Matrix) -> Matrix {
-    Matrix(matrix.0, matrix.2, matrix.1, matrix.3)
+    matrix.transpose()
 }
 
 fn analyze_slice(slice: &[i32]) {
-    println!("first element of the slice: {}", slice[0]);
+    match slice.first() {
+        Some(first) => println!("first element of the slice: {}", first),
+        None => println!("the slice is empty"),
+    }
     println!("the slice has {} elements", slice.len());
+    match (
+        stats::mean(slice),
+        stats::median(slice),
+        stats::stddev(slice),
+    ) {
+        (Ok(mean), Ok(median), Ok(stddev)) => println!(
+            "mean {:.2}, median {:.2}, standard deviation {:.2}",
+            mean, median, stddev
+        ),
+        (Err(err), _, _) => println!("no statistics: {}", err),
+        _ => unreachable!("mean, median and stddev fail on the same input"),
+    }
 }
 
 fn main() {
@@ -70,6 +83,24 @@ fn main() {
     println!("1 << 5 is {}", 1 << 5);
     println!("0x80 >> 2 is 0x{:x}", 0x80u32 >> 2);
 
+    // the same operations on sets of bit positions
+    let a: BitSet<1> = vec![0, 1].into_iter().collect();
+    let b: BitSet<1> = vec![0, 2].into_iter().collect();
+    println!("{:04b} AND {:04b} is {:04b}", a, b, &a & &b);
+    println!("{:04b} OR {:04b} is {:04b}", a, b, &a | &b);
+    println!("{:04b} XOR {:04b} is {:04b}", a, b, &a ^ &b);
+    let mut primes = BitVec::new();
+    for p in [2, 3, 5, 7, 11, 13, 97].iter() {
+        primes.set(*p);
+    }
+    println!(
+        "{} primes {:?}, {} of them below 10, the 5th is {:?}",
+        primes.count_ones(),
+        primes,
+        primes.rank(10),
+        primes.select(4)
+    );
+
     println!("One million is written as {}", 1_000_000u32);
 
     let long_tuple = (
@@ -98,10 +129,25 @@ fn main() {
     let (a, b, c, d) = tuple;
     println!("{:?}, {:?}, {:?}, {:?}", a, b, c, d);
 
-    let matrix = Matrix(1.1, 1.2, 2.1, 2.2);
+    let matrix = Matrix::new([[1.1, 1.2], [2.1, 2.2]]);
     println!("{:?}", matrix);
     println!("Matrix:\n{}", matrix);
     println!("Transpose:\n{}", transpose(matrix));
+    println!("Determinant: {}", matrix.determinant());
+    if let Some(inverse) = matrix.inverse() {
+        println!("Inverse:\n{:.2}", inverse);
+        println!("Matrix * Inverse:\n{:.2}", matrix * inverse);
+        println!("Identity:\n{:.2}", Matrix::identity());
+    }
+
+    // sizes are checked at compile time: 2x3 * 3x2 is 2x2
+    let wide = matrix::Matrix::new([[1, 2, 3], [4, 5, 6]]);
+    println!(
+        "{}\n*\n{}\n=\n{}",
+        wide,
+        wide.transpose(),
+        wide * wide.transpose()
+    );
 
     let xs: [i32; 5] = [1, 2, 3, 4, 5];
     let yx: [i32; 500] = [0; 500];
@@ -114,6 +160,20 @@ fn main() {
     analyze_slice(&xs);
     println!("borrow a section of the array as a slice");
     analyze_slice(&yx[1..4]);
+    println!("borrow an empty section of the array as a slice");
+    analyze_slice(&xs[5..]);
+
+    let scores = [72, 85, 91, 64, 85, 78, 99, 85, 70, 58];
+    println!("modes: {:?}", stats::mode(&scores));
+    println!("90th percentile: {:?}", stats::percentile(&scores, 90.0));
+    println!("lowest (index, value): {:?}", stats::min(&scores));
+    println!("highest (index, value): {:?}", stats::max(&scores));
+    if let Ok(histogram) = stats::Histogram::new(&scores, 4) {
+        if let Some(bin) = histogram.bins().iter().max_by_key(|bin| bin.count) {
+            println!("most scores are in {:.0}..{:.0}", bin.start, bin.end);
+        }
+        print!("{:.0}", histogram.width(20));
+    }
 
     // println!("{}", xs[5]); // out of bound causes compile error
 }
@@ -0,0 +1,350 @@
+// an R x C matrix stored as an array of rows.
+// the dimensions are const generics, so adding matrices of different sizes or multiplying
+// an R x C matrix by anything that does not have C rows is a compile error, not a panic.
+// determinant and inverse go through an LU decomposition with partial pivoting and are only
+// available for square matrices of floats.
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+// what a matrix can hold: numbers with a zero and a one
+pub trait Num:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_num {
+    ($zero:expr, $one:expr; $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+            }
+        )*
+    };
+}
+
+impl_num!(0, 1; i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_num!(0.0, 1.0; f32, f64);
+
+// numbers that can be divided without losing everything after the point
+pub trait Real: Num + Div<Output = Self> + Neg<Output = Self> {
+    const EPSILON: Self;
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+impl Real for f32 {
+    const EPSILON: Self = f32::EPSILON;
+}
+
+impl Real for f64 {
+    const EPSILON: Self = f64::EPSILON;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<T, const R: usize, const C: usize>([[T; C]; R]);
+
+impl<T: Copy, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn new(rows: [[T; C]; R]) -> Self {
+        Matrix(rows)
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Matrix<T, R, C> {
+    pub fn zero() -> Self {
+        Matrix([[T::ZERO; C]; R])
+    }
+
+    pub fn transpose(&self) -> Matrix<T, C, R> {
+        let mut transposed = Matrix::zero();
+        for (i, row) in self.0.iter().enumerate() {
+            for (j, &x) in row.iter().enumerate() {
+                transposed.0[j][i] = x;
+            }
+        }
+        transposed
+    }
+}
+
+impl<T: Num, const N: usize> Matrix<T, N, N> {
+    pub fn identity() -> Self {
+        let mut m = Self::zero();
+        for i in 0..N {
+            m.0[i][i] = T::ONE;
+        }
+        m
+    }
+}
+
+// the result of factoring a square matrix as P * A = L * U, with L and U packed into one
+// matrix (L's unit diagonal is implied) and P kept as a row permutation
+struct Lu<T, const N: usize> {
+    lu: Matrix<T, N, N>,
+    perm: [usize; N],
+    // -1 for an odd number of row swaps
+    sign: T,
+}
+
+impl<T: Real, const N: usize> Matrix<T, N, N> {
+    // `None` when the matrix is singular
+    fn lu(&self) -> Option<Lu<T, N>> {
+        let mut lu = *self;
+        let mut perm = [0; N];
+        for (i, p) in perm.iter_mut().enumerate() {
+            *p = i;
+        }
+        let mut sign = T::ONE;
+
+        // pivots this much smaller than the largest entry count as zero
+        let scale = self
+            .0
+            .iter()
+            .flatten()
+            .map(|x| x.abs())
+            .fold(T::ZERO, |a, b| if b > a { b } else { a });
+        let tolerance = scale * T::EPSILON;
+
+        for k in 0..N {
+            let pivot = (k..N)
+                .max_by(|&a, &b| {
+                    lu.0[a][k]
+                        .abs()
+                        .partial_cmp(&lu.0[b][k].abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(k);
+            if lu.0[pivot][k].abs() <= tolerance {
+                return None;
+            }
+            if pivot != k {
+                lu.0.swap(pivot, k);
+                perm.swap(pivot, k);
+                sign = -sign;
+            }
+            for i in k + 1..N {
+                let factor = lu.0[i][k] / lu.0[k][k];
+                lu.0[i][k] = factor;
+                for j in k + 1..N {
+                    lu.0[i][j] = lu.0[i][j] - factor * lu.0[k][j];
+                }
+            }
+        }
+        Some(Lu { lu, perm, sign })
+    }
+
+    pub fn determinant(&self) -> T {
+        match self.lu() {
+            Some(Lu { lu, sign, .. }) => (0..N).fold(sign, |det, i| det * lu.0[i][i]),
+            None => T::ZERO,
+        }
+    }
+
+    // `None` for a singular matrix
+    pub fn inverse(&self) -> Option<Self> {
+        let Lu { lu, perm, .. } = self.lu()?;
+        let mut inverse = Self::zero();
+        // solve L * U * x = P * e_col for every column of the identity
+        for col in 0..N {
+            let mut x = [T::ZERO; N];
+            for i in 0..N {
+                let b = if perm[i] == col { T::ONE } else { T::ZERO };
+                x[i] = dot_rest(b, &lu.0[i][..i], &x[..i]);
+            }
+            for i in (0..N).rev() {
+                x[i] = dot_rest(x[i], &lu.0[i][i + 1..], &x[i + 1..]) / lu.0[i][i];
+            }
+            for (row, &v) in inverse.0.iter_mut().zip(x.iter()) {
+                row[col] = v;
+            }
+        }
+        Some(inverse)
+    }
+}
+
+// `b` minus the dot product of `row` and `x`, one step of forward or back substitution
+fn dot_rest<T: Num>(b: T, row: &[T], x: &[T]) -> T {
+    row.iter().zip(x).fold(b, |sum, (&l, &x)| sum - l * x)
+}
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.0[row][col]
+    }
+}
+
+impl<T, const R: usize, const C: usize> IndexMut<(usize, usize)> for Matrix<T, R, C> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.0[row][col]
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Add for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        for (row, rhs_row) in self.0.iter_mut().zip(rhs.0.iter()) {
+            for (x, &y) in row.iter_mut().zip(rhs_row.iter()) {
+                *x = *x + y;
+            }
+        }
+        self
+    }
+}
+
+impl<T: Num, const R: usize, const C: usize> Sub for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let mut m = self;
+        for (row, rhs_row) in m.0.iter_mut().zip(rhs.0.iter()) {
+            for (x, &y) in row.iter_mut().zip(rhs_row.iter()) {
+                *x = *x - y;
+            }
+        }
+        m
+    }
+}
+
+// scalar multiplication
+impl<T: Num, const R: usize, const C: usize> Mul<T> for Matrix<T, R, C> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: T) -> Self {
+        for x in self.0.iter_mut().flatten() {
+            *x = *x * rhs;
+        }
+        self
+    }
+}
+
+// (R x C) * (C x K) = (R x K)
+impl<T: Num, const R: usize, const C: usize, const K: usize> Mul<Matrix<T, C, K>>
+    for Matrix<T, R, C>
+{
+    type Output = Matrix<T, R, K>;
+
+    fn mul(self, rhs: Matrix<T, C, K>) -> Matrix<T, R, K> {
+        let mut product = Matrix::zero();
+        for i in 0..R {
+            for j in 0..K {
+                product.0[i][j] = (0..C).fold(T::ZERO, |sum, k| sum + self.0[i][k] * rhs.0[k][j]);
+            }
+        }
+        product
+    }
+}
+
+// one `( .. )` line per row, every column right aligned to its widest entry.
+// a precision like `{:.2}` is applied to each entry
+impl<T: Display, const R: usize, const C: usize> Display for Matrix<T, R, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .0
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| match f.precision() {
+                        Some(precision) => format!("{:.*}", precision, x),
+                        None => x.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = (0..C)
+            .map(|j| {
+                cells
+                    .iter()
+                    .map(|row| row[j].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for (i, row) in cells.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            write!(f, "(")?;
+            for (cell, &width) in row.iter().zip(widths.iter()) {
+                write!(f, " {:>1$}", cell, width)?;
+            }
+            write!(f, " )")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close<const R: usize, const C: usize>(a: Matrix<f64, R, C>, b: Matrix<f64, R, C>) {
+        for (x, y) in a.0.iter().flatten().zip(b.0.iter().flatten()) {
+            assert!((x - y).abs() < 1e-9, "\n{}\n!=\n{}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_add_and_scale() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let b = Matrix::new([[6, 5, 4], [3, 2, 1]]);
+        assert_eq!(a + b, Matrix::new([[7; 3]; 2]));
+        assert_eq!(a - a, Matrix::zero());
+        assert_eq!(a * 2, Matrix::new([[2, 4, 6], [8, 10, 12]]));
+    }
+
+    #[test]
+    fn test_multiply_and_transpose() {
+        let a = Matrix::new([[1, 2, 3], [4, 5, 6]]);
+        let b: Matrix<i32, 3, 2> = a.transpose();
+        assert_eq!(b, Matrix::new([[1, 4], [2, 5], [3, 6]]));
+        // 2x3 * 3x2 = 2x2, 3x2 * 2x3 = 3x3
+        assert_eq!(a * b, Matrix::new([[14, 32], [32, 77]]));
+        assert_eq!((b * a)[(2, 2)], 45);
+        assert_eq!(a * Matrix::identity(), a);
+        assert_eq!((a * b).transpose(), b.transpose() * a.transpose());
+    }
+
+    #[test]
+    fn test_determinant() {
+        assert_eq!(Matrix::<f64, 2, 2>::identity().determinant(), 1.0);
+        assert_eq!(Matrix::new([[1.0, 2.0], [3.0, 4.0]]).determinant(), -2.0);
+        let m = Matrix::new([[2.0, -3.0, 1.0], [2.0, 0.0, -1.0], [1.0, 4.0, 5.0]]);
+        assert!((m.determinant() - 49.0).abs() < 1e-9);
+        // a row swap is needed for the zero in the top left corner
+        let swapped = Matrix::new([[0.0, 1.0], [1.0, 0.0]]);
+        assert_eq!(swapped.determinant(), -1.0);
+        assert_eq!(Matrix::new([[1.0, 2.0], [2.0, 4.0]]).determinant(), 0.0);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let m = Matrix::new([[4.0, 7.0, 2.0], [3.0, 6.0, 1.0], [2.0, 5.0, 3.0]]);
+        let inverse = m.inverse().unwrap();
+        assert_close(m * inverse, Matrix::identity());
+        assert_close(inverse * m, Matrix::identity());
+        assert_close(inverse.inverse().unwrap(), m);
+
+        assert_eq!(Matrix::new([[1.0, 2.0], [2.0, 4.0]]).inverse(), None);
+        assert_eq!(Matrix::<f64, 3, 3>::zero().inverse(), None);
+    }
+
+    #[test]
+    fn test_display_aligns_columns() {
+        let m = Matrix::new([[1.5, -20.0], [100.25, 3.0]]);
+        assert_eq!(m.to_string(), "(    1.5 -20 )\n( 100.25   3 )");
+        assert_eq!(format!("{:.1}", m), "(   1.5 -20.0 )\n( 100.2   3.0 )");
+    }
+}
@@ -0,0 +1,390 @@
+// descriptive statistics over slices of numbers.
+// every primitive number works through `ToF64`. integers wider than 53 bits are rounded to the
+// nearest `f64` for the arithmetic, so e.g. the mean of large `u64`s is approximate; `mode`,
+// `min` and `max` compare the values themselves and stay exact.
+// every function returns `StatsError::Empty` for an empty slice and `StatsError::NotANumber`
+// when a NaN would make the answer meaningless, none of them panics.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsError {
+    Empty,
+    NotANumber,
+    // percentiles go from 0 to 100
+    InvalidPercentile(f64),
+    // a histogram needs at least one bin
+    NoBins,
+}
+
+impl Display for StatsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Empty => write!(f, "no data"),
+            StatsError::NotANumber => write!(f, "the data contains NaN"),
+            StatsError::InvalidPercentile(p) => write!(f, "percentile {} is not in 0..=100", p),
+            StatsError::NoBins => write!(f, "a histogram needs at least one bin"),
+        }
+    }
+}
+
+impl Error for StatsError {}
+
+// `Into<f64>` stops at 32 bit integers because wider ones do not convert exactly,
+// this converts every primitive number and rounds where it has to
+pub trait ToF64: Copy + PartialOrd {
+    // what `mode` counts by, equal exactly when the values are
+    type Key: Eq + Hash;
+
+    fn to_f64(self) -> f64;
+    fn exact_key(self) -> Self::Key;
+}
+
+macro_rules! impl_to_f64 {
+    (integers: $($t:ty),*) => {
+        $(
+            impl ToF64 for $t {
+                type Key = $t;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn exact_key(self) -> $t {
+                    self
+                }
+            }
+        )*
+    };
+    // floats are counted by their bits, with -0.0 as 0.0 since the two compare equal
+    (floats: $($t:ty => $bits:ty),*) => {
+        $(
+            impl ToF64 for $t {
+                type Key = $bits;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn exact_key(self) -> $bits {
+                    (self + 0.0).to_bits()
+                }
+            }
+        )*
+    };
+}
+
+impl_to_f64!(integers: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_to_f64!(floats: f32 => u32, f64 => u64);
+
+// the data as f64, checked for emptiness and NaN
+fn values<T: ToF64>(data: &[T]) -> Result<Vec<f64>, StatsError> {
+    if data.is_empty() {
+        return Err(StatsError::Empty);
+    }
+    let values: Vec<f64> = data.iter().map(|&x| x.to_f64()).collect();
+    if values.iter().any(|x| x.is_nan()) {
+        return Err(StatsError::NotANumber);
+    }
+    Ok(values)
+}
+
+fn sorted<T: ToF64>(data: &[T]) -> Result<Vec<f64>, StatsError> {
+    let mut values = values(data)?;
+    // no NaN left, so every pair compares
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(values)
+}
+
+pub fn mean<T: ToF64>(data: &[T]) -> Result<f64, StatsError> {
+    let values = values(data)?;
+    Ok(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+// the middle value, or the mean of the two middle values for an even length
+pub fn median<T: ToF64>(data: &[T]) -> Result<f64, StatsError> {
+    percentile(data, 50.0)
+}
+
+// the most frequent values in order of first appearance, several when there is a tie
+pub fn mode<T: ToF64>(data: &[T]) -> Result<Vec<T>, StatsError> {
+    values(data)?;
+    let mut counts: HashMap<T::Key, usize> = HashMap::new();
+    for &x in data {
+        *counts.entry(x.exact_key()).or_insert(0) += 1;
+    }
+    let highest = counts.values().copied().max().unwrap_or(0);
+
+    let mut modes = Vec::new();
+    for &x in data {
+        // a mode is taken at its first appearance, then its count is cleared
+        if let Some(count) = counts.get_mut(&x.exact_key()) {
+            if *count == highest {
+                modes.push(x);
+                *count = 0;
+            }
+        }
+    }
+    Ok(modes)
+}
+
+// the population variance, the mean squared distance from the mean
+pub fn variance<T: ToF64>(data: &[T]) -> Result<f64, StatsError> {
+    let values = values(data)?;
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    Ok(values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64)
+}
+
+pub fn stddev<T: ToF64>(data: &[T]) -> Result<f64, StatsError> {
+    variance(data).map(f64::sqrt)
+}
+
+// the value below which `p` percent of the data falls, interpolating linearly between the
+// two nearest ranks
+pub fn percentile<T: ToF64>(data: &[T], p: f64) -> Result<f64, StatsError> {
+    if !(0.0..=100.0).contains(&p) {
+        return Err(StatsError::InvalidPercentile(p));
+    }
+    let sorted = sorted(data)?;
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (below, above) = (rank.floor() as usize, rank.ceil() as usize);
+    Ok(sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64))
+}
+
+// the smallest value and the index of its first occurrence
+pub fn min<T: ToF64>(data: &[T]) -> Result<(usize, T), StatsError> {
+    extreme(data, |candidate, best| candidate < best)
+}
+
+// the largest value and the index of its first occurrence
+pub fn max<T: ToF64>(data: &[T]) -> Result<(usize, T), StatsError> {
+    extreme(data, |candidate, best| candidate > best)
+}
+
+// compares `T` itself, not its rounded `f64`; with NaN ruled out every pair compares
+fn extreme<T: ToF64>(data: &[T], better: fn(T, T) -> bool) -> Result<(usize, T), StatsError> {
+    values(data)?;
+    let mut best = 0;
+    for (i, &value) in data.iter().enumerate().skip(1) {
+        if better(value, data[best]) {
+            best = i;
+        }
+    }
+    Ok((best, data[best]))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bin {
+    pub start: f64,
+    pub end: f64,
+    pub count: usize,
+}
+
+// the data counted into bins of equal width between its minimum and maximum.
+// every bin holds `start <= x < end`, except the last which includes its end
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bins: Vec<Bin>,
+    // the length of the longest bar when rendered
+    width: usize,
+}
+
+impl Histogram {
+    pub fn new<T: ToF64>(data: &[T], bins: usize) -> Result<Histogram, StatsError> {
+        if bins == 0 {
+            return Err(StatsError::NoBins);
+        }
+        let values = values(data)?;
+        let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        // all values equal: one unit wide bins so there is something to draw
+        let bin_width = if high > low {
+            (high - low) / bins as f64
+        } else {
+            1.0
+        };
+
+        let mut histogram: Vec<Bin> = (0..bins)
+            .map(|i| Bin {
+                start: low + bin_width * i as f64,
+                end: low + bin_width * (i + 1) as f64,
+                count: 0,
+            })
+            .collect();
+        for x in values {
+            let i = (((x - low) / bin_width) as usize).min(bins - 1);
+            histogram[i].count += 1;
+        }
+        Ok(Histogram {
+            bins: histogram,
+            width: 40,
+        })
+    }
+
+    pub fn bins(&self) -> &[Bin] {
+        &self.bins
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+}
+
+// one line per bin: its range, a bar of `#` scaled to the fullest bin, and its count.
+// a precision like `{:.1}` is used for the bin edges, 2 by default
+impl Display for Histogram {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let precision = f.precision().unwrap_or(2);
+        let edges: Vec<(String, String)> = self
+            .bins
+            .iter()
+            .map(|bin| {
+                (
+                    format!("{:.*}", precision, bin.start),
+                    format!("{:.*}", precision, bin.end),
+                )
+            })
+            .collect();
+        let edge_width = edges
+            .iter()
+            .map(|(start, end)| start.len().max(end.len()))
+            .max()
+            .unwrap_or(0);
+        let fullest = self.bins.iter().map(|bin| bin.count).max().unwrap_or(0);
+
+        for (i, (bin, (start, end))) in self.bins.iter().zip(edges.iter()).enumerate() {
+            // round up so only empty bins get no bar at all
+            let bar = (bin.count * self.width).div_ceil(fullest.max(1));
+            let close = if i + 1 == self.bins.len() { ']' } else { ')' };
+            writeln!(
+                f,
+                "[{:>w$}, {:>w$}{} {:<bar_w$} {}",
+                start,
+                end,
+                close,
+                "#".repeat(bar),
+                bin.count,
+                w = edge_width,
+                bar_w = self.width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_and_nan() {
+        let empty: [i32; 0] = [];
+        assert_eq!(mean(&empty), Err(StatsError::Empty));
+        assert_eq!(median(&empty), Err(StatsError::Empty));
+        assert_eq!(mode(&empty), Err(StatsError::Empty));
+        assert_eq!(min(&empty), Err(StatsError::Empty));
+        assert_eq!(Histogram::new(&empty, 3), Err(StatsError::Empty));
+        assert_eq!(variance(&[1.0, f64::NAN]), Err(StatsError::NotANumber));
+        assert_eq!(Histogram::new(&[1], 0), Err(StatsError::NoBins));
+    }
+
+    #[test]
+    fn test_central_tendency() {
+        let data = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(mean(&data), Ok(5.0));
+        assert_eq!(median(&data), Ok(4.5));
+        assert_eq!(median(&[3u8, 1, 2]), Ok(2.0));
+        assert_eq!(mode(&data), Ok(vec![4]));
+        assert_eq!(mode(&[1.5f32, 2.5, 2.5, 1.5]), Ok(vec![1.5, 2.5]));
+        assert_eq!(mode(&[-0.0, 1.0, 0.0]), Ok(vec![-0.0]));
+        let many: Vec<i32> = (0..100_000).map(|i| i % 1000).collect();
+        assert_eq!(mode(&many).unwrap().len(), 1000);
+    }
+
+    #[test]
+    fn test_wide_integers() {
+        let data: Vec<u64> = vec![10, 20, 20, 50];
+        assert_eq!(mean(&data), Ok(25.0));
+        assert_eq!(mode(&data), Ok(vec![20]));
+        assert_eq!(max(&[3i64, -9, 12]), Ok((2, 12)));
+        let lengths: Vec<usize> = ["a", "bbb", "cc"].iter().map(|s| s.len()).collect();
+        assert_eq!(median(&lengths), Ok(2.0));
+        assert_eq!(percentile(&[u128::MAX], 50.0), Ok(u128::MAX as f64));
+    }
+
+    // neighbours that round to the same f64 are still told apart
+    #[test]
+    fn test_wide_integers_stay_exact() {
+        assert_eq!(
+            mode(&[u64::MAX, u64::MAX - 1]),
+            Ok(vec![u64::MAX, u64::MAX - 1])
+        );
+        assert_eq!(
+            mode(&[u64::MAX - 1, u64::MAX, u64::MAX]),
+            Ok(vec![u64::MAX])
+        );
+        assert_eq!(max(&[u64::MAX - 1, u64::MAX]), Ok((1, u64::MAX)));
+        assert_eq!(min(&[u64::MAX, u64::MAX - 1]), Ok((1, u64::MAX - 1)));
+        assert_eq!(min(&[i128::MIN + 1, i128::MIN]), Ok((1, i128::MIN)));
+        assert_eq!(max(&[i128::MAX - 1, i128::MAX]), Ok((1, i128::MAX)));
+        assert_eq!(
+            mode(&[i128::MAX, i128::MAX - 1, i128::MAX - 1]),
+            Ok(vec![i128::MAX - 1])
+        );
+    }
+
+    #[test]
+    fn test_spread() {
+        let data = [2, 4, 4, 4, 5, 5, 7, 9];
+        assert_eq!(variance(&data), Ok(4.0));
+        assert_eq!(stddev(&data), Ok(2.0));
+        assert_eq!(variance(&[3.5]), Ok(0.0));
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let data = [15, 20, 35, 40, 50];
+        assert_eq!(percentile(&data, 0.0), Ok(15.0));
+        assert_eq!(percentile(&data, 100.0), Ok(50.0));
+        assert_eq!(percentile(&data, 25.0), Ok(20.0));
+        assert_eq!(percentile(&data, 40.0), Ok(29.0));
+        assert_eq!(
+            percentile(&data, 101.0),
+            Err(StatsError::InvalidPercentile(101.0))
+        );
+    }
+
+    #[test]
+    fn test_min_max_with_indices() {
+        let data = [3, -1, 7, -1, 7];
+        assert_eq!(min(&data), Ok((1, -1)));
+        assert_eq!(max(&data), Ok((2, 7)));
+    }
+
+    #[test]
+    fn test_histogram() {
+        let data = [1, 2, 2, 3, 3, 3, 4, 4, 4, 4, 5];
+        let histogram = Histogram::new(&data, 4).unwrap().width(10);
+        let counts: Vec<usize> = histogram.bins().iter().map(|bin| bin.count).collect();
+        // the maximum lands in the last bin
+        assert_eq!(counts, vec![1, 2, 3, 5]);
+        assert_eq!(counts.iter().sum::<usize>(), data.len());
+        assert_eq!(
+            format!("{:.1}", histogram),
+            "\
+[1.0, 2.0) ##         1
+[2.0, 3.0) ####       2
+[3.0, 4.0) ######     3
+[4.0, 5.0] ########## 5
+"
+        );
+
+        let same = Histogram::new(&[2.0, 2.0], 2).unwrap();
+        assert_eq!(same.bins()[0].count, 2);
+    }
+}
@@ -0,0 +1,346 @@
+// complex numbers over any float type.
+// `#[repr(C)]` keeps the layout of C's `float complex`/`double complex` (real part first),
+// so `Complex<f32>` can still be passed straight to libm's `csqrtf`, `ccosf`, ...
+// hello_world shares this file for its `Complex` as well.
+
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// the float operations complex math is built from, implemented for f32 and f64
+pub trait Float:
+    Copy
+    + PartialOrd
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn abs(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+                const TWO: Self = 2.0;
+
+                fn sqrt(self) -> Self { <$t>::sqrt(self) }
+                fn exp(self) -> Self { <$t>::exp(self) }
+                fn ln(self) -> Self { <$t>::ln(self) }
+                fn sin(self) -> Self { <$t>::sin(self) }
+                fn cos(self) -> Self { <$t>::cos(self) }
+                fn sinh(self) -> Self { <$t>::sinh(self) }
+                fn cosh(self) -> Self { <$t>::cosh(self) }
+                fn atan2(self, other: Self) -> Self { <$t>::atan2(self, other) }
+                fn hypot(self, other: Self) -> Self { <$t>::hypot(self, other) }
+                fn abs(self) -> Self { <$t>::abs(self) }
+                fn powf(self, n: Self) -> Self { <$t>::powf(self, n) }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+// `3.3+7.2i` the way C prints it, with or without `#`
+impl<T: Float> fmt::Debug for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.im < T::ZERO {
+            write!(f, "{}-{}i", self.re, -self.im)
+        } else {
+            write!(f, "{}+{}i", self.re, self.im)
+        }
+    }
+}
+
+impl<T> Complex<T> {
+    pub fn new(re: T, im: T) -> Complex<T> {
+        Complex { re, im }
+    }
+}
+
+impl<T: Float> Complex<T> {
+    pub fn i() -> Complex<T> {
+        Complex::new(T::ZERO, T::ONE)
+    }
+
+    pub fn conj(self) -> Complex<T> {
+        Complex::new(self.re, -self.im)
+    }
+
+    // modulus |z|
+    pub fn norm(self) -> T {
+        self.re.hypot(self.im)
+    }
+
+    // argument, the angle to the positive real axis in -pi..=pi
+    pub fn arg(self) -> T {
+        self.im.atan2(self.re)
+    }
+
+    // (modulus, argument)
+    pub fn to_polar(self) -> (T, T) {
+        (self.norm(), self.arg())
+    }
+
+    pub fn from_polar(r: T, theta: T) -> Complex<T> {
+        Complex::new(r * theta.cos(), r * theta.sin())
+    }
+
+    pub fn exp(self) -> Complex<T> {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    // principal branch of the natural logarithm
+    pub fn ln(self) -> Complex<T> {
+        Complex::new(self.norm().ln(), self.arg())
+    }
+
+    // principal square root, the result has a non-negative real part
+    pub fn sqrt(self) -> Complex<T> {
+        let r = self.norm();
+        let re = ((r + self.re) / T::TWO).sqrt();
+        let im = ((r - self.re) / T::TWO).sqrt();
+        // the sign of the imaginary part follows the input, negative zero included
+        if self.im < T::ZERO || (self.im == T::ZERO && (T::ONE / self.im) < T::ZERO) {
+            Complex::new(re, -im)
+        } else {
+            Complex::new(re, im)
+        }
+    }
+
+    // z^w = exp(w ln z), with 0^w = 0
+    pub fn powc(self, w: Complex<T>) -> Complex<T> {
+        if self.re == T::ZERO && self.im == T::ZERO {
+            return Complex::new(T::ZERO, T::ZERO);
+        }
+        (w * self.ln()).exp()
+    }
+
+    pub fn powf(self, n: T) -> Complex<T> {
+        let (r, theta) = self.to_polar();
+        Complex::from_polar(r.powf(n), theta * n)
+    }
+
+    // repeated multiplication, exact for small integer powers
+    pub fn powi(self, n: i32) -> Complex<T> {
+        let mut result = Complex::new(T::ONE, T::ZERO);
+        let mut base = self;
+        let mut e = n.unsigned_abs();
+        while e > 0 {
+            if e & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            e >>= 1;
+        }
+        if n < 0 {
+            Complex::new(T::ONE, T::ZERO) / result
+        } else {
+            result
+        }
+    }
+
+    pub fn sin(self) -> Complex<T> {
+        Complex::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+
+    pub fn cos(self) -> Complex<T> {
+        Complex::new(
+            self.re.cos() * self.im.cosh(),
+            -(self.re.sin() * self.im.sinh()),
+        )
+    }
+
+    pub fn tan(self) -> Complex<T> {
+        self.sin() / self.cos()
+    }
+}
+
+impl<T: Float> Add for Complex<T> {
+    type Output = Complex<T>;
+
+    fn add(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl<T: Float> Sub for Complex<T> {
+    type Output = Complex<T>;
+
+    fn sub(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl<T: Float> Mul for Complex<T> {
+    type Output = Complex<T>;
+
+    fn mul(self, rhs: Complex<T>) -> Complex<T> {
+        Complex::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl<T: Float> Div for Complex<T> {
+    type Output = Complex<T>;
+
+    fn div(self, rhs: Complex<T>) -> Complex<T> {
+        let denominator = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex::new(
+            (self.re * rhs.re + self.im * rhs.im) / denominator,
+            (self.im * rhs.re - self.re * rhs.im) / denominator,
+        )
+    }
+}
+
+impl<T: Float> Neg for Complex<T> {
+    type Output = Complex<T>;
+
+    fn neg(self) -> Complex<T> {
+        Complex::new(-self.re, -self.im)
+    }
+}
+
+// scaling by a real number
+impl<T: Float> Mul<T> for Complex<T> {
+    type Output = Complex<T>;
+
+    fn mul(self, rhs: T) -> Complex<T> {
+        Complex::new(self.re * rhs, self.im * rhs)
+    }
+}
+
+impl<T: Float> Div<T> for Complex<T> {
+    type Output = Complex<T>;
+
+    fn div(self, rhs: T) -> Complex<T> {
+        Complex::new(self.re / rhs, self.im / rhs)
+    }
+}
+
+impl<T: Float> From<T> for Complex<T> {
+    fn from(re: T) -> Complex<T> {
+        Complex::new(re, T::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{E, PI};
+
+    fn assert_close(a: Complex<f64>, b: Complex<f64>) {
+        assert!((a - b).norm() < 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(format!("{:?}", Complex::new(3.5f32, -2.0)), "3.5-2i");
+        assert_eq!(format!("{:?}", Complex::new(0.0f64, 1.5)), "0+1.5i");
+        assert_eq!(format!("{:#?}", Complex::new(3.3f64, 7.2)), "3.3+7.2i");
+        assert_eq!(
+            format!(
+                "{:?}",
+                vec![Complex::new(1.0f32, 0.0), Complex::new(0.0, -1.0)]
+            ),
+            "[1+0i, 0-1i]"
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(3.0, -1.0);
+        assert_eq!(a + b, Complex::new(4.0, 1.0));
+        assert_eq!(a - b, Complex::new(-2.0, 3.0));
+        assert_eq!(a * b, Complex::new(5.0, 5.0));
+        assert_close(a * b / b, a);
+        assert_eq!(-a, Complex::new(-1.0, -2.0));
+        assert_eq!(a * 2.0, Complex::new(2.0, 4.0));
+        assert_eq!(a.conj(), Complex::new(1.0, -2.0));
+        assert_eq!(Complex::<f64>::i() * Complex::i(), Complex::from(-1.0));
+    }
+
+    #[test]
+    fn test_polar() {
+        let z = Complex::new(3.0, 4.0);
+        assert_eq!(z.norm(), 5.0);
+        let (r, theta) = z.to_polar();
+        assert_close(Complex::from_polar(r, theta), z);
+        assert_eq!(Complex::new(-1.0, 0.0).arg(), PI);
+    }
+
+    #[test]
+    fn test_exp_ln() {
+        // Euler's identity
+        assert_close((Complex::<f64>::i() * PI).exp(), Complex::new(-1.0, 0.0));
+        assert_close(Complex::new(E, 0.0).ln(), Complex::new(1.0, 0.0));
+        let z = Complex::new(0.5, -1.5);
+        assert_close(z.ln().exp(), z);
+    }
+
+    #[test]
+    fn test_powers_and_roots() {
+        assert_close(Complex::new(-1.0, 0.0).sqrt(), Complex::i());
+        assert_close(Complex::new(-1.0, -0.0).sqrt(), -Complex::<f64>::i());
+        let z = Complex::new(1.0, 1.0);
+        assert_close(z.sqrt() * z.sqrt(), z);
+        assert_close(z.powi(2), Complex::new(0.0, 2.0));
+        assert_close(z.powi(-2), Complex::new(0.0, -0.5));
+        assert_close(z.powf(2.0), z * z);
+        assert_close(z.powc(Complex::from(3.0)), z * z * z);
+        assert_eq!(Complex::new(0.0, 0.0).powc(z), Complex::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_trig() {
+        let z = Complex::new(0.3, -0.7);
+        let one = Complex::from(1.0);
+        assert_close(z.sin() * z.sin() + z.cos() * z.cos(), one);
+        assert_close(z.tan(), z.sin() / z.cos());
+        // cos z = (e^iz + e^-iz) / 2
+        let iz = Complex::i() * z;
+        assert_close(z.cos(), (iz.exp() + (-iz).exp()) / 2.0);
+    }
+
+    #[test]
+    fn test_layout_matches_c() {
+        use std::mem::{align_of, size_of};
+        assert_eq!(size_of::<Complex<f32>>(), 2 * size_of::<f32>());
+        assert_eq!(align_of::<Complex<f32>>(), align_of::<f32>());
+        assert_eq!(size_of::<Complex<f64>>(), 2 * size_of::<f64>());
+    }
+}
@@ -19,7 +19,6 @@
 #![allow(dead_code)]
 use std::error::Error;
 use std::f32::consts::E;
-use std::fmt;
 use std::fmt::Formatter;
 use std::fs::{File, OpenOptions};
 use std::io::{BufRead, Read, Write};
@@ -31,6 +30,10 @@ use std::sync::mpsc::{Receiver, Sender};
 use std::thread::JoinHandle;
 use std::{env, fs, io, thread, time};
 
+mod complex;
+
+use complex::Complex;
+
 static NTHREADS: u32 = 3;
 
 static LOREM_IP_SUM: &str =
@@ -46,45 +49,38 @@ proident, sunt in culpa qui officia deserunt mollit anim id est laborum.
 // Rust provides a FFI to C libraries. Foreign function must be declared inside an extern block
 // annotated with a #[link] attribute containing the name of the foreign library
 
-// minimal implementation of single precision complex numbers
-#[repr(C)]
-#[derive(Copy, Clone)]
-struct Complex {
-    re: f32,
-    im: f32,
-}
-
-impl fmt::Debug for Complex {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if self.im < 0. {
-            write!(f, "{}-{}i", self.re, -self.im)
-        } else {
-            write!(f, "{}+{}i", self.re, self.im)
-        }
-    }
-}
+// `Complex<T>` is `#[repr(C)]`, so `Complex<f32>` has the layout of C's `float complex`
 
 // this extern block links to the libm library
 #[link(name = "m")]
 extern "C" {
     // this is a foreign function that computes the square root of a single precision complex number
-    fn csqrtf(z: Complex) -> Complex;
-    fn ccosf(z: Complex) -> Complex;
+    fn csqrtf(z: Complex<f32>) -> Complex<f32>;
+    fn ccosf(z: Complex<f32>) -> Complex<f32>;
+    fn csinf(z: Complex<f32>) -> Complex<f32>;
+    fn cexpf(z: Complex<f32>) -> Complex<f32>;
+    fn clogf(z: Complex<f32>) -> Complex<f32>;
 }
 
 // since calling foreign functions is considered unsafe, it's common to write safe wrappers around them.
-fn cos(z: Complex) -> Complex {
+fn cos(z: Complex<f32>) -> Complex<f32> {
     unsafe { ccosf(z) }
 }
 
 fn show_ffi() {
-    let z = Complex { re: -1., im: 0. };
+    let z = Complex::new(-1., 0.);
     // calling a foreign function is an unsafe operation
     let z_sqrt = unsafe { csqrtf(z) };
     println!("the square root of {:?} is {:?}", &z, z_sqrt);
 
     // calling safe API wrapped around unsafe function
     println!("cos({:?}) = {:?}", &z, cos(z));
+
+    // the pure Rust versions agree with libm
+    println!("sqrt({:?}) = {:?} in Rust", &z, z.sqrt());
+    println!("cos({:?}) = {:?} in Rust", &z, z.cos());
+    let w = Complex::new(0.5f64, -2.0);
+    println!("exp({:?}) = {:?}, ln = {:?}", w, w.exp(), w.ln());
 }
 
 // This is the `main` thread
@@ -563,3 +559,38 @@ fn show_threads_simple() {
         let _ = child.join();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // f32 libm results and the pure Rust ones may differ in the last bits
+    fn assert_close(a: Complex<f32>, b: Complex<f32>) {
+        assert!(
+            (a - b).norm() <= 1e-5 * (1.0 + b.norm()),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_complex_matches_libm() {
+        let samples = [
+            Complex::new(-1.0, 0.0),
+            Complex::new(0.0, 1.0),
+            Complex::new(3.0, -4.0),
+            Complex::new(-0.25, 0.75),
+            Complex::new(2.5, 1.5),
+        ];
+        for &z in samples.iter() {
+            unsafe {
+                assert_close(z.sqrt(), csqrtf(z));
+                assert_close(z.cos(), ccosf(z));
+                assert_close(z.sin(), csinf(z));
+                assert_close(z.exp(), cexpf(z));
+                assert_close(z.ln(), clogf(z));
+            }
+        }
+    }
+}
@@ -0,0 +1,478 @@
+// a farm of `Box<dyn Animal>`, sheep from main.rs next to cows, goats and dogs.
+// every species is a small state machine: sheep are shorn and regrow their wool, cows and goats
+// are milked, dogs are fed. `Farm` runs them all a day at a time and logs what happened.
+// what the farmer gets round to on a day is rolled from a seeded `Rng`, so a seed always gives
+// the same log.
+use std::fmt;
+
+use super::{Animal, Breed};
+
+// xorshift64*: not for anything secret, but small and the same on every machine
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // xorshift stays at 0 forever
+        Rng(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // a number in `low..=high`, counted in u64 so `between(0, u32::MAX)` has room
+    pub fn between(&mut self, low: u32, high: u32) -> u32 {
+        assert!(low <= high, "between({}, {}): low is above high", low, high);
+        let range = u64::from(high) - u64::from(low) + 1;
+        (u64::from(low) + self.next_u64() % range) as u32
+    }
+
+    // true `numerator` times out of `denominator`, never for a denominator of 0
+    pub fn chance(&mut self, numerator: u32, denominator: u32) -> bool {
+        denominator > 0 && self.between(1, denominator) <= numerator
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    Sheared { wool: u32 },
+    WoolGrown,
+    Fed,
+    Hungry,
+    Milked { litres: u32 },
+    Escaped,
+    Returned,
+    Barked,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Sheared { wool } => write!(f, "was shorn for {} kg of wool", wool),
+            Event::WoolGrown => write!(f, "has grown its wool back"),
+            Event::Fed => write!(f, "was fed"),
+            Event::Hungry => write!(f, "went hungry"),
+            Event::Milked { litres } => write!(f, "gave {} l of milk", litres),
+            Event::Escaped => write!(f, "escaped from the pen"),
+            Event::Returned => write!(f, "was found and brought back"),
+            Event::Barked => write!(f, "barked at the fox"),
+        }
+    }
+}
+
+// a cow has to be fed before it can be milked again
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stomach {
+    Empty,
+    Full,
+}
+
+pub struct Cow {
+    name: &'static str,
+    stomach: Stomach,
+}
+
+impl Breed for Cow {
+    fn new(name: &'static str) -> Cow {
+        Cow {
+            name,
+            stomach: Stomach::Empty,
+        }
+    }
+}
+
+impl Animal for Cow {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn notice(&self) -> &'static str {
+        match self.stomach {
+            Stomach::Empty => "mooooo!",
+            Stomach::Full => "moo",
+        }
+    }
+
+    // the hay runs short on one day in four
+    fn live_day(&mut self, rng: &mut Rng) -> Option<Event> {
+        match self.stomach {
+            Stomach::Empty if rng.chance(3, 4) => {
+                self.stomach = Stomach::Full;
+                Some(Event::Fed)
+            }
+            Stomach::Empty => Some(Event::Hungry),
+            Stomach::Full => {
+                self.stomach = Stomach::Empty;
+                Some(Event::Milked {
+                    litres: rng.between(8, 12),
+                })
+            }
+        }
+    }
+}
+
+// a goat is milked every day it stays in the pen
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Whereabouts {
+    Penned,
+    Loose,
+}
+
+pub struct Goat {
+    name: &'static str,
+    whereabouts: Whereabouts,
+}
+
+impl Breed for Goat {
+    fn new(name: &'static str) -> Goat {
+        Goat {
+            name,
+            whereabouts: Whereabouts::Penned,
+        }
+    }
+}
+
+impl Animal for Goat {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn notice(&self) -> &'static str {
+        match self.whereabouts {
+            Whereabouts::Penned => "meeeh",
+            Whereabouts::Loose => "meeeh, from somewhere far away",
+        }
+    }
+
+    fn live_day(&mut self, rng: &mut Rng) -> Option<Event> {
+        match self.whereabouts {
+            Whereabouts::Penned if rng.chance(1, 5) => {
+                self.whereabouts = Whereabouts::Loose;
+                Some(Event::Escaped)
+            }
+            Whereabouts::Penned => Some(Event::Milked {
+                litres: rng.between(1, 3),
+            }),
+            Whereabouts::Loose if rng.chance(1, 2) => {
+                self.whereabouts = Whereabouts::Penned;
+                Some(Event::Returned)
+            }
+            Whereabouts::Loose => None,
+        }
+    }
+}
+
+// a dog is fed every third day and gets hungrier in between
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Appetite {
+    Fed,
+    Peckish,
+    Hungry,
+}
+
+pub struct Dog {
+    name: &'static str,
+    appetite: Appetite,
+}
+
+impl Breed for Dog {
+    fn new(name: &'static str) -> Dog {
+        Dog {
+            name,
+            appetite: Appetite::Hungry,
+        }
+    }
+}
+
+impl Animal for Dog {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn notice(&self) -> &'static str {
+        match self.appetite {
+            Appetite::Hungry => "*whines*",
+            _ => "woof!",
+        }
+    }
+
+    // a dog with something in its stomach keeps watch, and sometimes there is a fox
+    fn live_day(&mut self, rng: &mut Rng) -> Option<Event> {
+        let next = match self.appetite {
+            Appetite::Hungry => {
+                self.appetite = Appetite::Fed;
+                return Some(Event::Fed);
+            }
+            Appetite::Fed => Appetite::Peckish,
+            Appetite::Peckish => Appetite::Hungry,
+        };
+        self.appetite = next;
+        if rng.chance(1, 3) {
+            Some(Event::Barked)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Farm {
+    animals: Vec<Box<dyn Animal>>,
+    rng: Rng,
+    day: u32,
+    log: Vec<String>,
+    wool: u32,
+    milk: u32,
+}
+
+impl Farm {
+    pub fn new(seed: u64) -> Farm {
+        Farm {
+            animals: Vec::new(),
+            rng: Rng::new(seed),
+            day: 0,
+            log: Vec::new(),
+            wool: 0,
+            milk: 0,
+        }
+    }
+
+    pub fn add(mut self, animal: impl Animal + 'static) -> Farm {
+        self.animals.push(Box::new(animal));
+        self
+    }
+
+    pub fn animals(&self) -> &[Box<dyn Animal>] {
+        &self.animals
+    }
+
+    // runs the next day, the animals in the order they were added, and returns its log lines
+    pub fn next_day(&mut self) -> &[String] {
+        self.day += 1;
+        let start = self.log.len();
+        for animal in self.animals.iter_mut() {
+            if let Some(event) = animal.live_day(&mut self.rng) {
+                match event {
+                    Event::Sheared { wool } => self.wool += wool,
+                    Event::Milked { litres } => self.milk += litres,
+                    _ => {}
+                }
+                self.log
+                    .push(format!("day {}: {} {}", self.day, animal.name(), event));
+            }
+        }
+        &self.log[start..]
+    }
+
+    pub fn run(&mut self, days: u32) {
+        for _ in 0..days {
+            self.next_day();
+        }
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn log(&self) -> &[String] {
+        &self.log
+    }
+
+    // kg of wool shorn so far
+    pub fn wool(&self) -> u32 {
+        self.wool
+    }
+
+    // litres of milk so far
+    pub fn milk(&self) -> u32 {
+        self.milk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Sheep, WOOL_REGROWTH_DAYS};
+
+    fn farm(seed: u64) -> Farm {
+        Farm::new(seed)
+            .add(Sheep::new("Dolly"))
+            .add(Cow::new("Daisy"))
+            .add(Goat::new("Billy"))
+            .add(Dog::new("Rex"))
+    }
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(0);
+        assert_ne!(rng.next_u64(), 0);
+        for _ in 0..1000 {
+            assert!((2..=4).contains(&rng.between(2, 4)));
+        }
+        assert!(Rng::new(7).chance(1, 1));
+        assert!(!Rng::new(7).chance(0, 1));
+        assert!(!Rng::new(7).chance(3, 0));
+        assert_eq!(rng.between(5, 5), 5);
+        let mut seen_high = false;
+        for _ in 0..1000 {
+            seen_high |= rng.between(0, u32::MAX) > u32::MAX / 2;
+        }
+        assert!(seen_high);
+    }
+
+    #[test]
+    #[should_panic(expected = "between(4, 2): low is above high")]
+    fn test_between_backwards() {
+        Rng::new(1).between(4, 2);
+    }
+
+    #[test]
+    fn test_same_seed_same_log() {
+        let mut first = farm(42);
+        let mut second = farm(42);
+        first.run(30);
+        second.run(30);
+        assert_eq!(first.log(), second.log());
+        assert_eq!((first.wool(), first.milk()), (second.wool(), second.milk()));
+
+        let mut other = farm(43);
+        other.run(30);
+        assert_ne!(first.log(), other.log());
+    }
+
+    #[test]
+    fn test_log() {
+        let mut farm = farm(2024);
+        farm.run(4);
+        let log: Vec<&str> = farm.log().iter().map(|line| line.as_str()).collect();
+        assert_eq!(
+            log,
+            vec![
+                "day 1: Dolly was shorn for 3 kg of wool",
+                "day 1: Daisy was fed",
+                "day 1: Billy gave 3 l of milk",
+                "day 1: Rex was fed",
+                "day 2: Daisy gave 12 l of milk",
+                "day 2: Billy gave 3 l of milk",
+                "day 3: Daisy was fed",
+                "day 3: Billy gave 1 l of milk",
+                "day 4: Daisy gave 12 l of milk",
+                "day 4: Billy gave 3 l of milk",
+                "day 4: Rex was fed",
+            ]
+        );
+        assert_eq!(farm.day(), 4);
+        assert_eq!(farm.wool(), 3);
+        assert_eq!(farm.milk(), 34);
+    }
+
+    #[test]
+    fn test_next_day_returns_only_that_day() {
+        let mut farm = farm(2024);
+        farm.run(2);
+        let day_3 = farm.next_day().to_vec();
+        assert!(day_3.iter().all(|line| line.starts_with("day 3: ")));
+        assert_eq!(&farm.log()[farm.log().len() - day_3.len()..], &day_3[..]);
+    }
+
+    // shorn sooner or later, then woolly again after exactly `days`
+    fn check_regrowth(mut sheep: Sheep, days: u32) {
+        let mut rng = Rng::new(1);
+        let mut waited = 0;
+        while !matches!(sheep.live_day(&mut rng), Some(Event::Sheared { .. })) {
+            waited += 1;
+            assert!(waited < 100);
+        }
+        assert_eq!(sheep.notice(), "baaaaaaah?");
+        for _ in 1..days {
+            assert_eq!(sheep.live_day(&mut rng), None);
+        }
+        assert_eq!(sheep.live_day(&mut rng), Some(Event::WoolGrown));
+        assert_eq!(sheep.notice(), "baaaaaaah!");
+    }
+
+    #[test]
+    fn test_wool_regrows() {
+        check_regrowth(Sheep::new("Dolly"), WOOL_REGROWTH_DAYS);
+        check_regrowth(Sheep::new("Shaun").regrowth_days(9), 9);
+        check_regrowth(Sheep::new("Timmy").regrowth_days(1), 1);
+    }
+
+    #[test]
+    fn test_regrowth_on_the_farm() {
+        let mut farm = Farm::new(7).add(Sheep::new("Shaun").regrowth_days(10));
+        farm.run(60);
+        // a day `day N: ...` happened on
+        let day = |line: &str| -> u32 { line[4..line.find(':').unwrap()].parse().unwrap() };
+        let log = farm.log();
+        assert!(log.len() >= 4);
+        for pair in log.chunks_exact(2) {
+            assert!(pair[0].ends_with("kg of wool"), "{}", pair[0]);
+            assert!(pair[1].ends_with("has grown its wool back"), "{}", pair[1]);
+            assert_eq!(day(&pair[1]) - day(&pair[0]), 10);
+        }
+    }
+
+    #[test]
+    fn test_cow_is_milked_only_after_feeding() {
+        let mut daisy = Cow::new("Daisy");
+        let mut rng = Rng::new(5);
+        let mut fed = false;
+        for _ in 0..100 {
+            match daisy.live_day(&mut rng) {
+                Some(Event::Fed) => fed = true,
+                Some(Event::Milked { litres }) => {
+                    assert!(fed);
+                    assert!((8..=12).contains(&litres));
+                    fed = false;
+                }
+                Some(Event::Hungry) => assert!(!fed),
+                other => panic!("a cow does not {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_goat_is_not_milked_while_loose() {
+        let mut billy = Goat::new("Billy");
+        let mut rng = Rng::new(9);
+        let mut loose = false;
+        for _ in 0..200 {
+            match billy.live_day(&mut rng) {
+                Some(Event::Milked { .. }) => assert!(!loose),
+                Some(Event::Escaped) => loose = true,
+                Some(Event::Returned) | None => {
+                    assert!(loose);
+                    loose = billy.notice() != "meeeh";
+                }
+                other => panic!("a goat does not {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_dog_is_fed_every_third_day() {
+        let mut rex = Dog::new("Rex");
+        let mut rng = Rng::new(3);
+        assert_eq!(rex.notice(), "*whines*");
+        for day in 0..30 {
+            let event = rex.live_day(&mut rng);
+            assert_eq!(event == Some(Event::Fed), day % 3 == 0);
+        }
+    }
+
+    #[test]
+    fn test_animals_are_trait_objects() {
+        let farm = farm(1);
+        let names: Vec<&str> = farm.animals().iter().map(|a| a.name()).collect();
+        assert_eq!(names, vec!["Dolly", "Daisy", "Billy", "Rex"]);
+        let animal: &dyn Animal = farm.animals()[1].as_ref();
+        assert_eq!(animal.notice(), "mooooo!");
+    }
+}
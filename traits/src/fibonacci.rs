@@ -0,0 +1,24 @@
+// the Fibonacci sequence as an `Iterator`: 1, 2, 3, 5, 8, ...
+// functions shares this file to race its memoized Fibonacci against it.
+
+#![allow(dead_code)]
+
+pub struct Fibonacci {
+    curr: u32,
+    next: u32,
+}
+
+impl Iterator for Fibonacci {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let new_next: Self::Item = self.curr + self.next;
+        self.curr = self.next;
+        self.next = new_next;
+        Some(self.curr)
+    }
+}
+
+pub fn fibonacci() -> Fibonacci {
+    Fibonacci { curr: 1, next: 1 }
+}
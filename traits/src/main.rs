@@ -1,13 +1,43 @@
 use std::ops::Add;
 
+mod farm;
+mod fibonacci;
+
+use farm::{Cow, Dog, Event, Farm, Goat, Rng};
+use fibonacci::fibonacci;
+
 fn main() {
     // you can use Trait static method and type annotation to call implement method
-    let mut dolly: Sheep = Animal::new("Dolly");
+    let mut dolly: Sheep = Breed::new("Dolly");
 
     dolly.talk();
     dolly.shear();
     dolly.talk();
 
+    // without `new`, `Animal` can be boxed, so one farm keeps every species side by side
+    let mut farm = Farm::new(2024)
+        .add(dolly)
+        .add(Sheep::new("Shaun").regrowth_days(2))
+        .add(Cow::new("Daisy"))
+        .add(Goat::new("Billy"))
+        .add(Dog::new("Rex"));
+    for animal in farm.animals() {
+        animal.talk();
+    }
+    for _ in 0..7 {
+        for line in farm.next_day() {
+            println!("{}", line);
+        }
+    }
+    farm.run(23);
+    println!(
+        "after {} days, {} things happened: {} kg of wool, {} l of milk",
+        farm.day(),
+        farm.log().len(),
+        farm.wool(),
+        farm.milk()
+    );
+
     let _one_second = Seconds(1);
     // println!("One second looks like: {:?}", _one_second); // no Debug trait impl for Seconds
     // let _this_is_true = (_one_second == _one_second); // no PartialEq trait impl for Seconds
@@ -97,26 +127,6 @@ struct Nil;
 #[derive(Clone, Debug)]
 struct Pair(Box<i32>, Box<i32>);
 
-struct Fibonacci {
-    curr: u32,
-    next: u32,
-}
-
-impl Iterator for Fibonacci {
-    type Item = u32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let new_next: Self::Item = self.curr + self.next;
-        self.curr = self.next;
-        self.next = new_next;
-        Some(self.curr)
-    }
-}
-
-fn fibonacci() -> Fibonacci {
-    Fibonacci { curr: 1, next: 1 }
-}
-
 struct Droppable {
     name: &'static str,
 }
@@ -175,15 +185,20 @@ impl Inches {
 
 struct Seconds(i32);
 
+// days a shorn sheep takes to grow its wool back, unless it is given its own
+const WOOL_REGROWTH_DAYS: u32 = 4;
+
 struct Sheep {
     naked: bool,
+    // days since it was shorn
+    regrowth: u32,
+    // days its wool takes to grow back
+    regrowth_days: u32,
     name: &'static str,
 }
 
+// `Animal` has only methods on `&self` and `&mut self`, so it can be a trait object: `Box<dyn Animal>`
 trait Animal {
-    // static method signature: `Self` refers to the implement type.
-    fn new(name: &'static str) -> Self;
-
     fn name(&self) -> &'static str;
     fn notice(&self) -> &'static str;
 
@@ -191,31 +206,60 @@ trait Animal {
     fn talk(&self) {
         println!("{} syas {}", self.name(), self.notice());
     }
+
+    // one day on the farm, and what happened to the animal that day
+    fn live_day(&mut self, rng: &mut Rng) -> Option<Event>;
+}
+
+// a static method returns `Self`, whose size a trait object does not know,
+// so the constructor lives in its own trait
+trait Breed: Animal {
+    // static method signature: `Self` refers to the implement type.
+    fn new(name: &'static str) -> Self;
 }
 
 impl Sheep {
+    fn regrowth_days(mut self, days: u32) -> Sheep {
+        assert!(days > 0, "wool that grows back in 0 days");
+        self.regrowth_days = days;
+        self
+    }
+
     fn is_naked(&self) -> bool {
         self.naked
     }
 
-    fn shear(&mut self) {
+    // shearing without the printing, false when there is no wool to cut
+    fn cut_wool(&mut self) -> bool {
         if self.is_naked() {
-            println!("{} is already naked...", self.name);
-        } else {
+            return false;
+        }
+        self.naked = true;
+        self.regrowth = 0;
+        true
+    }
+
+    fn shear(&mut self) {
+        if self.cut_wool() {
             println!("{} get a haircut!", self.name);
-            self.naked = true;
+        } else {
+            println!("{} is already naked...", self.name);
         }
     }
 }
 
-impl Animal for Sheep {
+impl Breed for Sheep {
     fn new(name: &'static str) -> Sheep {
         Sheep {
             naked: false,
+            regrowth: 0,
+            regrowth_days: WOOL_REGROWTH_DAYS,
             name: &name,
         }
     }
+}
 
+impl Animal for Sheep {
     fn name(&self) -> &'static str {
         self.name
     }
@@ -231,4 +275,23 @@ impl Animal for Sheep {
     fn talk(&self) {
         println!("{} paues briefly... {}", self.name, self.notice())
     }
+
+    // the farmer shears a woolly sheep on half the days, then the wool grows back
+    fn live_day(&mut self, rng: &mut Rng) -> Option<Event> {
+        if self.is_naked() {
+            self.regrowth += 1;
+            if self.regrowth < self.regrowth_days {
+                return None;
+            }
+            self.naked = false;
+            Some(Event::WoolGrown)
+        } else if rng.chance(1, 2) {
+            self.cut_wool();
+            Some(Event::Sheared {
+                wool: rng.between(2, 4),
+            })
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,42 @@
+// explain what `as` does to a number, step by step:
+//
+//     cargo run --bin cast-explain -- 1000 u8
+//     cargo run --bin cast-explain -- 64.4321 u8 --from f32
+//
+// without `--from` the value is read as an i128, a u128 or an f64, whichever fits first.
+
+#[path = "../cast.rs"]
+mod cast;
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: cast-explain VALUE TYPE [--from TYPE]";
+
+fn run(args: &[String]) -> Result<Vec<String>, String> {
+    let (value, to, from) = match args {
+        [value, to] => (value, to, None),
+        [value, to, flag, from] if flag == "--from" => (value, to, Some(from.as_str())),
+        _ => return Err("expected a value and a type".to_owned()),
+    };
+    let from = match from {
+        Some(from) => from,
+        None => cast::infer_type(value).ok_or_else(|| format!("{} is not a number", value))?,
+    };
+    cast::explain_named(value, from, to)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            process::exit(2);
+        }
+    }
+}
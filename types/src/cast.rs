@@ -0,0 +1,682 @@
+// numeric casts that say what `as` did to the value.
+// `cast::<From, To>(v)` gives the same value as `v as To`, tagged with how it got there:
+// - `Exact`: the value is unchanged
+// - `Wrapped`: an integer that does not fit, 2^bits of the target was added or subtracted
+//   `steps` times until it did
+// - `Truncated`: precision was lost, the fraction of a float or the low bits of a large integer
+// - `Saturated`: out of range for the target, clamped to its MIN or MAX (a float overflowing
+//   to infinity counts too, and NaN, which becomes 0 as an integer)
+// `explain` writes out the reasoning like the comments in main do, src/bin/cast-explain.rs
+// puts it on the command line.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cast<T> {
+    Exact(T),
+    // positive `steps` subtracted 2^bits, negative ones added it
+    Wrapped { value: T, steps: i128 },
+    Truncated(T),
+    Saturated(T),
+}
+
+impl<T: Copy> Cast<T> {
+    pub fn value(&self) -> T {
+        match *self {
+            Cast::Exact(v) | Cast::Truncated(v) | Cast::Saturated(v) => v,
+            Cast::Wrapped { value, .. } => value,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Cast::Exact(_))
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Cast::Exact(_) => "exact",
+            Cast::Wrapped { .. } => "wrapped",
+            Cast::Truncated(_) => "truncated",
+            Cast::Saturated(_) => "saturated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Signed,
+    Unsigned,
+    Float,
+}
+
+// any primitive value, wide enough for all of them: `UInt` only for a `u128` above `i128::MAX`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    Int(i128),
+    UInt(u128),
+    Float(f64),
+}
+
+impl Value {
+    fn unsigned(u: u128) -> Value {
+        if u <= i128::MAX as u128 {
+            Value::Int(u as i128)
+        } else {
+            Value::UInt(u)
+        }
+    }
+
+    // floor(self / 2^shift), for integers and `shift` below 128
+    fn floor_shift(self, shift: u32) -> i128 {
+        match self {
+            Value::Int(i) => i >> shift,
+            Value::UInt(u) => (u >> shift) as i128,
+            Value::Float(_) => unreachable!("only integers are shifted"),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{}", i),
+            Value::UInt(u) => write!(f, "{}", u),
+            Value::Float(x) => write!(f, "{:?}", x),
+        }
+    }
+}
+
+pub trait Primitive: Copy + fmt::Debug + fmt::Display + FromStr {
+    const NAME: &'static str;
+    const KIND: Kind;
+    const BITS: u32;
+    // bits of precision, the significand of a float or all bits of an integer
+    const PRECISION: u32;
+
+    fn to_value(self) -> Value;
+    // the same as `as`, from the widest type of each kind
+    fn from_value(v: Value) -> Self;
+    fn min() -> Self;
+    fn max() -> Self;
+}
+
+macro_rules! primitive {
+    ($($t:ident: $kind:ident, $precision:expr, |$v:ident| $to_value:expr;)*) => {
+        $(impl Primitive for $t {
+            const NAME: &'static str = stringify!($t);
+            const KIND: Kind = Kind::$kind;
+            const BITS: u32 = (std::mem::size_of::<$t>() * 8) as u32;
+            const PRECISION: u32 = $precision;
+
+            fn to_value(self) -> Value {
+                let $v = self;
+                $to_value
+            }
+
+            fn from_value(v: Value) -> $t {
+                match v {
+                    Value::Int(i) => i as $t,
+                    Value::UInt(u) => u as $t,
+                    Value::Float(f) => f as $t,
+                }
+            }
+
+            fn min() -> $t {
+                $t::MIN
+            }
+
+            fn max() -> $t {
+                $t::MAX
+            }
+        })*
+    };
+}
+
+primitive! {
+    u8: Unsigned, 8, |v| Value::unsigned(v.into());
+    u16: Unsigned, 16, |v| Value::unsigned(v.into());
+    u32: Unsigned, 32, |v| Value::unsigned(v.into());
+    u64: Unsigned, 64, |v| Value::unsigned(v.into());
+    u128: Unsigned, 128, |v| Value::unsigned(v);
+    usize: Unsigned, usize::BITS, |v| Value::unsigned(v as u128);
+    i8: Signed, 8, |v| Value::Int(v.into());
+    i16: Signed, 16, |v| Value::Int(v.into());
+    i32: Signed, 32, |v| Value::Int(v.into());
+    i64: Signed, 64, |v| Value::Int(v.into());
+    i128: Signed, 128, |v| Value::Int(v);
+    isize: Signed, isize::BITS, |v| Value::Int(v as i128);
+    f32: Float, f32::MANTISSA_DIGITS, |v| Value::Float(v.into());
+    f64: Float, f64::MANTISSA_DIGITS, |v| Value::Float(v);
+}
+
+// 2^bits as text, 2^128 does not fit in a `u128`
+fn modulus(bits: u32) -> String {
+    if bits < 128 {
+        (1u128 << bits).to_string()
+    } else {
+        "2^128".to_owned()
+    }
+}
+
+pub fn cast<F: Primitive, T: Primitive>(v: F) -> Cast<T> {
+    let value = v.to_value();
+    let result = T::from_value(value);
+    match (value, T::KIND) {
+        (Value::Float(f), Kind::Float) => {
+            let r = float(result);
+            if f.is_nan() || r == f {
+                Cast::Exact(result)
+            } else if r.is_infinite() {
+                Cast::Saturated(result)
+            } else {
+                Cast::Truncated(result)
+            }
+        }
+        (Value::Float(f), kind) => {
+            let t = f.trunc();
+            // everything from 2^(bits - 1) or 2^bits up is out of range, unlike MAX that
+            // power of two is a float exactly
+            let (low, high) = match kind {
+                Kind::Signed => (
+                    -(2f64.powi(T::BITS as i32 - 1)),
+                    2f64.powi(T::BITS as i32 - 1),
+                ),
+                _ => (0.0, 2f64.powi(T::BITS as i32)),
+            };
+            if f.is_nan() || t < low || t >= high {
+                Cast::Saturated(result)
+            } else if t != f {
+                Cast::Truncated(result)
+            } else {
+                Cast::Exact(result)
+            }
+        }
+        (_, Kind::Float) => {
+            let r = float(result);
+            let exact = match value {
+                Value::Int(i) => r < 2f64.powi(127) && r as i128 == i,
+                Value::UInt(u) => r < 2f64.powi(128) && r as u128 == u,
+                Value::Float(_) => unreachable!(),
+            };
+            if exact {
+                Cast::Exact(result)
+            } else if r.is_infinite() {
+                Cast::Saturated(result)
+            } else {
+                Cast::Truncated(result)
+            }
+        }
+        (_, kind) => {
+            // the unsigned result is value mod 2^bits, the signed one value + 2^(bits - 1)
+            // mod 2^bits, less 2^(bits - 1): how many times 2^bits went is the floor of that
+            // quotient
+            let halves = value.floor_shift(T::BITS - 1);
+            let steps = if kind == Kind::Signed {
+                (halves + 1) >> 1
+            } else {
+                halves >> 1
+            };
+            if steps == 0 {
+                Cast::Exact(result)
+            } else {
+                Cast::Wrapped {
+                    value: result,
+                    steps,
+                }
+            }
+        }
+    }
+}
+
+fn float<T: Primitive>(v: T) -> f64 {
+    match v.to_value() {
+        Value::Float(f) => f,
+        _ => unreachable!("not a float"),
+    }
+}
+
+// significant bits of an integer, from the highest to the lowest one that is set
+fn significant_bits(value: Value) -> u32 {
+    let magnitude = match value {
+        Value::Int(i) => i.unsigned_abs(),
+        Value::UInt(u) => u,
+        Value::Float(_) => unreachable!(),
+    };
+    if magnitude == 0 {
+        0
+    } else {
+        128 - magnitude.leading_zeros() - magnitude.trailing_zeros()
+    }
+}
+
+fn range<T: Primitive>() -> String {
+    if T::KIND == Kind::Float {
+        format!("-{max:?}..={max:?}", max = T::max())
+    } else {
+        format!("{}..={}", T::min(), T::max())
+    }
+}
+
+// the reasoning behind `cast::<F, T>(v)`, one step a line, the last one the result
+pub fn explain<F: Primitive, T: Primitive>(v: F) -> Vec<String> {
+    let cast = cast::<F, T>(v);
+    let result = cast.value();
+    let value = v.to_value();
+    // `Debug` keeps floats short, 1e300 instead of all its digits
+    let (v, result) = (format!("{:?}", v), format!("{:?}", result));
+    let mut lines = Vec::new();
+    match (cast, F::KIND, T::KIND) {
+        (Cast::Wrapped { steps, .. }, _, kind) => {
+            let m = modulus(T::BITS);
+            let (sign, verb) = if steps > 0 {
+                ('-', "subtracted")
+            } else {
+                ('+', "added")
+            };
+            lines.push(format!(
+                "{} does not fit in {} ({}), {} is {} until it does",
+                v,
+                T::NAME,
+                range::<T>(),
+                m,
+                verb
+            ));
+            if steps.unsigned_abs() <= 4 {
+                let mut sum = v.clone();
+                for _ in 0..steps.unsigned_abs() {
+                    sum += &format!(" {} {}", sign, m);
+                }
+                lines.push(format!("{} = {}", sum, result));
+            } else {
+                lines.push(format!(
+                    "{} {} {} * {} = {}",
+                    v,
+                    sign,
+                    steps.unsigned_abs(),
+                    m,
+                    result
+                ));
+            }
+            if kind == Kind::Signed {
+                let bits = T::BITS as usize;
+                let pattern = match cast.value().to_value() {
+                    Value::Int(i) => i as u128,
+                    other => unreachable!("{:?} from a signed type", other),
+                };
+                let mask = if bits == 128 {
+                    u128::MAX
+                } else {
+                    (1 << bits) - 1
+                };
+                let negative = (pattern >> (bits - 1)) & 1 == 1;
+                lines.push(format!(
+                    "in bits that is {:0width$b}: the top bit is {}, so as {} it is {}",
+                    pattern & mask,
+                    if negative { "set" } else { "clear" },
+                    T::NAME,
+                    if negative { "negative" } else { "not negative" },
+                    width = bits
+                ));
+            }
+        }
+        (_, Kind::Float, Kind::Float) => lines.push(match cast {
+            Cast::Exact(_) => format!("{} is exactly representable in {}", v, T::NAME),
+            Cast::Saturated(_) => format!(
+                "{} is beyond {}::MAX ({:?}), so it becomes {}",
+                v,
+                T::NAME,
+                T::max(),
+                result
+            ),
+            _ => format!(
+                "{} has more precision than the {} bits {} keeps, it rounds to the nearest \
+                 {}, which as an f64 is {}",
+                v,
+                T::PRECISION,
+                T::NAME,
+                T::NAME,
+                cast.value().to_value()
+            ),
+        }),
+        (_, Kind::Float, _) => lines.push(match cast {
+            Cast::Exact(_) => format!("{} is a whole number in {} ({})", v, T::NAME, range::<T>()),
+            Cast::Truncated(_) => {
+                format!("the fraction of {} is dropped, rounding towards zero", v)
+            }
+            _ if matches!(value, Value::Float(f) if f.is_nan()) => {
+                format!("NaN has no integer value, it becomes {}", result)
+            }
+            _ => format!(
+                "{} is outside {} ({}), so it saturates to {}",
+                v,
+                T::NAME,
+                range::<T>(),
+                result
+            ),
+        }),
+        (_, _, Kind::Float) => lines.push(match cast {
+            Cast::Exact(_) => format!("{} is exactly representable in {}", v, T::NAME),
+            Cast::Saturated(_) => format!(
+                "{} is beyond {}::MAX ({:?}), so it becomes {}",
+                v,
+                T::NAME,
+                T::max(),
+                result
+            ),
+            _ => format!(
+                "{} needs {} significant bits, {} keeps {}, so it rounds to {}",
+                v,
+                significant_bits(value),
+                T::NAME,
+                T::PRECISION,
+                cast.value().to_value()
+            ),
+        }),
+        _ => lines.push(format!("{} fits in {} ({})", v, T::NAME, range::<T>())),
+    }
+    lines.push(format!(
+        "{}_{} as {} = {} ({})",
+        v,
+        F::NAME,
+        T::NAME,
+        result,
+        cast.kind()
+    ));
+    lines
+}
+
+macro_rules! for_type_name {
+    ($name:expr, $t:ident => $body:expr) => {
+        match $name {
+            "u8" => {
+                type $t = u8;
+                Some($body)
+            }
+            "u16" => {
+                type $t = u16;
+                Some($body)
+            }
+            "u32" => {
+                type $t = u32;
+                Some($body)
+            }
+            "u64" => {
+                type $t = u64;
+                Some($body)
+            }
+            "u128" => {
+                type $t = u128;
+                Some($body)
+            }
+            "usize" => {
+                type $t = usize;
+                Some($body)
+            }
+            "i8" => {
+                type $t = i8;
+                Some($body)
+            }
+            "i16" => {
+                type $t = i16;
+                Some($body)
+            }
+            "i32" => {
+                type $t = i32;
+                Some($body)
+            }
+            "i64" => {
+                type $t = i64;
+                Some($body)
+            }
+            "i128" => {
+                type $t = i128;
+                Some($body)
+            }
+            "isize" => {
+                type $t = isize;
+                Some($body)
+            }
+            "f32" => {
+                type $t = f32;
+                Some($body)
+            }
+            "f64" => {
+                type $t = f64;
+                Some($body)
+            }
+            _ => None,
+        }
+    };
+}
+
+// `explain` with the types given by name, `value` parsed as `from`
+pub fn explain_named(value: &str, from: &str, to: &str) -> Result<Vec<String>, String> {
+    let unknown = |name: &str| format!("unknown type {}", name);
+    for_type_name!(from, F => {
+        let v: F = value
+            .parse()
+            .map_err(|_| format!("{} is not a {}", value, from))?;
+        for_type_name!(to, T => explain::<F, T>(v)).ok_or_else(|| unknown(to))?
+    })
+    .ok_or_else(|| unknown(from))
+}
+
+// the narrowest of i128, u128 and f64 that parses `value` exactly as written
+pub fn infer_type(value: &str) -> Option<&'static str> {
+    if value.parse::<i128>().is_ok() {
+        Some("i128")
+    } else if value.parse::<u128>().is_ok() {
+        Some("u128")
+    } else if value.parse::<f64>().is_ok() {
+        Some("f64")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    // every u8 and every i8 into every type, against `as` and `TryFrom`
+    macro_rules! check_all_targets {
+        ($v:expr, $($t:ident),*) => {
+            $({
+                let c = cast::<_, $t>($v);
+                assert_eq!(c.value(), $v as $t, "{} as {}", $v, stringify!($t));
+                let fits = $t::try_from($v).is_ok();
+                match c {
+                    Cast::Exact(_) => assert!(fits, "{} as {}", $v, stringify!($t)),
+                    Cast::Wrapped { steps, .. } => {
+                        assert!(!fits);
+                        match 1i128.checked_shl($t::BITS) {
+                            Some(m) if $t::BITS < 127 => {
+                                assert_eq!(i128::from($v) - c.value() as i128, steps * m)
+                            }
+                            // only a negative i8 wraps into the 128 bit types, 2^128 once
+                            _ => assert_eq!(steps, -1),
+                        }
+                    }
+                    other => panic!("{} as {}: {:?}", $v, stringify!($t), other),
+                }
+            })*
+        };
+    }
+
+    #[test]
+    fn test_every_u8_and_i8() {
+        for v in u8::MIN..=u8::MAX {
+            check_all_targets!(v, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+            assert_eq!(cast::<u8, f32>(v), Cast::Exact(f32::from(v)));
+            assert_eq!(cast::<u8, f64>(v), Cast::Exact(f64::from(v)));
+        }
+        for v in i8::MIN..=i8::MAX {
+            check_all_targets!(v, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+            assert_eq!(cast::<i8, f32>(v), Cast::Exact(f32::from(v)));
+            assert_eq!(cast::<i8, f64>(v), Cast::Exact(f64::from(v)));
+        }
+    }
+
+    // every i16 into u8 and i8: wrapped exactly when it does not fit, and 256 times the steps
+    // is how far the value moved
+    #[test]
+    fn test_every_i16_into_u8_and_i8() {
+        for v in i16::MIN..=i16::MAX {
+            for c in [
+                cast::<i16, u8>(v).value() as i16,
+                cast::<i16, i8>(v).value() as i16,
+            ] {
+                assert_eq!((v as i32 - c as i32).rem_euclid(256), 0);
+            }
+            match cast::<i16, u8>(v) {
+                Cast::Exact(r) => assert!(u8::try_from(v) == Ok(r)),
+                Cast::Wrapped { value, steps } => {
+                    assert!(u8::try_from(v).is_err());
+                    assert_eq!(v as i128 - value as i128, steps * 256);
+                }
+                other => panic!("{:?}", other),
+            }
+            match cast::<i16, i8>(v) {
+                Cast::Exact(r) => assert!(i8::try_from(v) == Ok(r)),
+                Cast::Wrapped { value, steps } => {
+                    assert!(i8::try_from(v).is_err());
+                    assert_eq!(v as i128 - value as i128, steps * 256);
+                }
+                other => panic!("{:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_widest_integers() {
+        assert_eq!(
+            cast::<u128, i128>(u128::MAX),
+            Cast::Wrapped {
+                value: -1,
+                steps: 1
+            }
+        );
+        assert_eq!(
+            cast::<i128, u128>(-1),
+            Cast::Wrapped {
+                value: u128::MAX,
+                steps: -1
+            }
+        );
+        assert_eq!(
+            cast::<u128, u8>(u128::MAX),
+            Cast::Wrapped {
+                value: 255,
+                steps: (u128::MAX >> 8) as i128
+            }
+        );
+        assert_eq!(
+            cast::<i128, i8>(i128::MIN),
+            Cast::Wrapped {
+                value: 0,
+                steps: -(1 << 119)
+            }
+        );
+        assert_eq!(cast::<i64, i128>(i64::MIN), Cast::Exact(i64::MIN as i128));
+    }
+
+    #[test]
+    fn test_floats_to_integers() {
+        assert_eq!(cast::<f32, u8>(64.4321), Cast::Truncated(64));
+        assert_eq!(cast::<f64, i32>(-2.9), Cast::Truncated(-2));
+        assert_eq!(cast::<f64, u8>(-0.5), Cast::Truncated(0));
+        assert_eq!(cast::<f64, u8>(255.0), Cast::Exact(255));
+        assert_eq!(cast::<f64, u8>(256.0), Cast::Saturated(255));
+        assert_eq!(cast::<f64, u8>(-1.0), Cast::Saturated(0));
+        assert_eq!(cast::<f64, i8>(-128.9), Cast::Truncated(-128));
+        assert_eq!(cast::<f64, i8>(-129.0), Cast::Saturated(-128));
+        assert_eq!(cast::<f64, i32>(f64::NAN), Cast::Saturated(0));
+        assert_eq!(cast::<f32, u64>(f32::INFINITY), Cast::Saturated(u64::MAX));
+        // i64::MAX is no f64, the nearest one, 2^63, is already out of range
+        assert_eq!(cast::<f64, i64>(i64::MAX as f64), Cast::Saturated(i64::MAX));
+        assert_eq!(cast::<f64, i64>(-(2f64.powi(63))), Cast::Exact(i64::MIN));
+    }
+
+    #[test]
+    fn test_into_floats() {
+        assert_eq!(cast::<i32, f32>(16_777_216), Cast::Exact(16_777_216.0));
+        assert_eq!(cast::<i32, f32>(16_777_217), Cast::Truncated(16_777_216.0));
+        assert_eq!(cast::<u64, f64>(u64::MAX), Cast::Truncated(u64::MAX as f64));
+        assert_eq!(cast::<i128, f64>(i128::MIN), Cast::Exact(i128::MIN as f64));
+        assert_eq!(
+            cast::<i128, f64>(i128::MAX),
+            Cast::Truncated(i128::MAX as f64)
+        );
+        assert_eq!(cast::<u128, f32>(u128::MAX), Cast::Saturated(f32::INFINITY));
+        assert_eq!(cast::<f64, f32>(0.5), Cast::Exact(0.5));
+        assert_eq!(cast::<f64, f32>(0.1), Cast::Truncated(0.1));
+        assert_eq!(cast::<f64, f32>(1e300), Cast::Saturated(f32::INFINITY));
+        assert!(cast::<f64, f32>(f64::NAN).is_exact());
+        assert_eq!(cast::<f32, f64>(0.1), Cast::Exact(0.1f32 as f64));
+    }
+
+    #[test]
+    fn test_explain_matches_the_comments() {
+        let lines = explain::<i32, u8>(1000);
+        assert_eq!(lines[1], "1000 - 256 - 256 - 256 = 232");
+        assert_eq!(lines[2], "1000_i32 as u8 = 232 (wrapped)");
+        assert_eq!(explain::<i8, u8>(-1)[1], "-1 + 256 = 255");
+        let lines = explain::<u8, i8>(128);
+        assert_eq!(lines[1], "128 - 256 = -128");
+        assert_eq!(
+            lines[2],
+            "in bits that is 10000000: the top bit is set, so as i8 it is negative"
+        );
+        assert_eq!(explain::<u32, i8>(232)[1], "232 - 256 = -24");
+        assert_eq!(explain::<i32, u8>(256)[1], "256 - 256 = 0");
+        assert_eq!(
+            explain::<u32, u8>(1_000_000)[1],
+            "1000000 - 3906 * 256 = 64"
+        );
+        assert_eq!(
+            explain::<f32, u8>(64.4321),
+            vec![
+                "the fraction of 64.4321 is dropped, rounding towards zero",
+                "64.4321_f32 as u8 = 64 (truncated)"
+            ]
+        );
+        assert_eq!(
+            explain::<i32, u16>(1000),
+            vec![
+                "1000 fits in u16 (0..=65535)",
+                "1000_i32 as u16 = 1000 (exact)"
+            ]
+        );
+        assert_eq!(
+            explain::<i32, f32>(16_777_217)[0],
+            "16777217 needs 25 significant bits, f32 keeps 24, so it rounds to 16777216.0"
+        );
+    }
+
+    #[test]
+    fn test_explain_named() {
+        assert_eq!(
+            explain_named("1000", "i32", "u8").unwrap().last().unwrap(),
+            "1000_i32 as u8 = 232 (wrapped)"
+        );
+        assert_eq!(
+            explain_named("300.5", "f64", "u8").unwrap()[0],
+            "300.5 is outside u8 (0..=255), so it saturates to 255"
+        );
+        assert_eq!(
+            explain_named("1000", "u8", "i8"),
+            Err("1000 is not a u8".to_owned())
+        );
+        assert_eq!(
+            explain_named("1", "u8", "u7"),
+            Err("unknown type u7".to_owned())
+        );
+        assert_eq!(infer_type("-5"), Some("i128"));
+        assert_eq!(infer_type(&u128::MAX.to_string()), Some("u128"));
+        assert_eq!(infer_type("2.5"), Some("f64"));
+        assert_eq!(infer_type("five"), None);
+    }
+}
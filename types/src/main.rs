@@ -9,6 +9,8 @@ type U64T = u64;
 
 // 类型别名的用途主要用来节省样板代码
 
+mod cast;
+
 fn main() {
     let decimal = 64.4321_f32; // also 64.4321f32
     println!("{}", decimal);
@@ -46,6 +48,14 @@ fn main() {
     // 232 - 127 - 1 -128 = -24
     println!("232 as a i8 is: {}", 232_u32 as i8);
 
+    // cast.rs works these steps out for any two numeric types, see also `cargo run --bin cast-explain`
+    for line in cast::explain::<u32, i8>(232) {
+        println!("  {}", line);
+    }
+    for line in cast::explain::<f32, u8>(64.4321) {
+        println!("  {}", line);
+    }
+
     let x = 1u8;
     let y = 2u32;
     let z = 3f32;